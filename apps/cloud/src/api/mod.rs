@@ -13,12 +13,15 @@ use crate::{
 };
 use axum::{
     extract::Query,
+    http::{header::USER_AGENT, HeaderMap},
     response::{IntoResponse, Response},
     routing::{delete, get, post, put, Router},
     Extension, Json,
 };
 use chrono::Utc;
-use cloud_database::{Claims, MakeToken, RefreshToken, User, UserQuery, UserToken};
+use cloud_database::{
+    Claims, LoginClient, MakeToken, RefreshToken, StorageError, User, UserQuery, UserToken,
+};
 use jwst_logger::{error, info, instrument, tracing};
 use lib0::any::Any;
 use std::sync::Arc;
@@ -151,6 +154,7 @@ pub async fn query_user(
 /// - Return 200 ok and `token`.
 /// - Return 400 bad request if request parameter error.
 /// - Return 401 unauthorized if user unauthorized.
+/// - Return 429 too many requests if the email is locked after failed logins.
 /// - Return 500 internal server error if database error.
 #[utoipa::path(post, tag = "Workspace", context_path = "/api/user", path = "/token",
 request_body(content = MakeToken, description = "Request body for make token",content_type = "application/json",example = json!({
@@ -166,21 +170,25 @@ responses(
     }
     )),
     (status = 400, description = "Request parameter error."),
-    (status = 401, description = "Unauthorized."),
+    (status = 401, description = "Unauthorized, or a two-factor code is required."),
+    (status = 403, description = "The account is suspended."),
+    (status = 429, description = "Too many failed logins."),
     (status = 500, description = "Server error, please try again later.")
 )
 )]
-#[instrument(skip(ctx, payload))] // payload need to be safe
+#[instrument(skip(ctx, headers, payload))] // payload need to be safe
 pub async fn make_token(
     Extension(ctx): Extension<Arc<Context>>,
+    headers: HeaderMap,
     Json(payload): Json<MakeToken>,
 ) -> Response {
     info!("make_token enter");
+    let client = login_client(&headers);
     // TODO: too complex type, need to refactor
     let (user, refresh) = match payload {
         MakeToken::DebugCreateUser(user) => {
             if cfg!(debug_assertions) || std::env::var("JWST_DEV").is_ok() {
-                if let Ok(model) = ctx.db.create_user(user).await {
+                if let Ok(Some(model)) = ctx.db.create_user(user).await {
                     (Ok(Some(model)), None)
                 } else {
                     return ErrorStatus::BadRequest.into_response();
@@ -191,7 +199,7 @@ pub async fn make_token(
         }
         MakeToken::DebugLoginUser(user) => {
             if cfg!(debug_assertions) || std::env::var("JWST_DEV").is_ok() {
-                (ctx.db.user_login(user).await, None)
+                (ctx.db.user_login_with_client(user, &client).await, None)
             } else {
                 return ErrorStatus::BadRequest.into_response();
             }
@@ -204,8 +212,15 @@ pub async fn make_token(
                 .decode_google_token(token, ctx.config.refresh_token_expires_in)
                 .await
             {
-                Ok(claims) => match ctx.db.firebase_user_login(&claims).await {
+                Ok(claims) => match ctx
+                    .db
+                    .firebase_user_login_with_client(&claims, &client)
+                    .await
+                {
                     Ok(user) => (Ok(Some(user)), None),
+                    Err(StorageError::Suspended) => {
+                        return ErrorStatus::Suspended.into_response();
+                    }
                     Err(e) => {
                         error!("failed to auth: {:?}", e,);
                         return ErrorStatus::InternalServerError.into_response();
@@ -230,7 +245,10 @@ pub async fn make_token(
                 return ErrorStatus::Unauthorized.into_response();
             }
 
-            (ctx.db.refresh_token(data).await, Some(token))
+            (
+                ctx.db.refresh_token_with_client(data, &client).await,
+                Some(token),
+            )
         }
     };
 
@@ -257,7 +275,9 @@ pub async fn make_token(
                     name: user.name,
                     email: user.email,
                     avatar_url: user.avatar_url,
-                    created_at: user.created_at.unwrap_or_default().naive_local(),
+                    created_at: user.created_at.unwrap_or_default().into(),
+                    timezone: user.timezone,
+                    email_verified: user.email_verified,
                 },
             };
             let token = ctx.key.sign_jwt(&claims);
@@ -265,6 +285,9 @@ pub async fn make_token(
             Json(UserToken { token, refresh }).into_response()
         }
         Ok(None) => ErrorStatus::Unauthorized.into_response(),
+        Err(StorageError::Locked { .. }) => ErrorStatus::TooManyRequests.into_response(),
+        Err(StorageError::TotpRequired) => ErrorStatus::TotpRequired.into_response(),
+        Err(StorageError::Suspended) => ErrorStatus::Suspended.into_response(),
         Err(e) => {
             error!("Failed to make token: {:?}", e);
             ErrorStatus::InternalServerError.into_response()
@@ -272,6 +295,22 @@ pub async fn make_token(
     }
 }
 
+/// Where a login came from, kept in the user's login history. Behind a
+/// proxy the client is the first address of `X-Forwarded-For`.
+fn login_client(headers: &HeaderMap) -> LoginClient {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    LoginClient {
+        ip: header("x-forwarded-for")
+            .and_then(|ips| ips.split(',').next().map(|ip| ip.trim().to_string())),
+        user_agent: header(USER_AGENT.as_str()),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -294,6 +333,7 @@ mod test {
                 password: "xxx".to_string(),
             })
             .await
+            .unwrap()
             .unwrap();
         let ctx = Arc::new(context);
         let app = super::make_rest_route(ctx.clone()).layer(Extension(ctx.clone()));
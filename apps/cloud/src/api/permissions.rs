@@ -8,7 +8,9 @@ use axum::{
     response::{IntoResponse, Response},
     Extension, Json,
 };
-use cloud_database::{Claims, CreatePermission, PermissionType, UserCred};
+use cloud_database::{
+    Claims, CreatePermission, CreatePermissionOutcome, PermissionType, StorageError, UserCred,
+};
 use image::ImageOutputFormat;
 use jwst::{error, BlobStorage};
 use jwst_logger::{info, instrument, tracing};
@@ -95,6 +97,7 @@ pub async fn get_members(
     responses(
         (status = 200, description = "Invite member successfully"),
         (status = 400, description = "Request parameter error."),
+        (status = 404, description = "Workspace not found or private."),
         (status = 409, description = "Invitation failed."),
         (status = 500, description = "Server error, please try again later.")
     )
@@ -137,8 +140,14 @@ pub async fn invite_member(
             .create_permission(&data.email, workspace_id.clone(), PermissionType::Write)
             .await
         {
-            Ok(Some(p)) => p,
-            Ok(None) => return ErrorStatus::ConflictInvitation.into_response(),
+            Ok(CreatePermissionOutcome::Created(id, user)) => (id, user),
+            Ok(CreatePermissionOutcome::AlreadyMember) => {
+                return ErrorStatus::ConflictInvitation.into_response()
+            }
+            Ok(CreatePermissionOutcome::WorkspaceNotInvitable) => {
+                return ErrorStatus::NotFoundWorkspace(workspace_id).into_response()
+            }
+            Err(StorageError::InvalidEmail(_)) => return ErrorStatus::BadRequest.into_response(),
             Err(e) => {
                 error!("Failed to create permission: {}", e);
                 return ErrorStatus::InternalServerError.into_response();
@@ -220,7 +220,11 @@ pub async fn update_workspace(
         }
     }
 
-    match ctx.db.update_workspace(workspace_id.clone(), payload).await {
+    match ctx
+        .db
+        .update_workspace_by(workspace_id.clone(), payload, &claims.user.id)
+        .await
+    {
         Ok(Some(data)) => {
             ctx.user_channel
                 .update_workspace(workspace_id.clone(), ctx.clone())
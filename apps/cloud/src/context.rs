@@ -1,12 +1,12 @@
 use super::{api::UserChannel, config::Config, utils::create_debug_collaboration_workspace};
 use crate::application::blob_service::BlobService;
-use cloud_database::CloudDatabase;
+use cloud_database::{CloudDatabase, ConnectOptions};
 use cloud_infra::{FirebaseContext, KeyContext, MailContext};
 use jwst::SearchResults;
 use jwst_logger::{error, info, warn};
 use jwst_rpc::{BroadcastChannels, BroadcastType, RpcContextImpl};
 use jwst_storage::{BlobStorageType, JwstStorage, MixedBucketDBParam};
-use std::{collections::HashMap, path::Path};
+use std::{collections::HashMap, path::Path, time::Duration};
 use tempfile::{tempdir, TempDir};
 use tokio::sync::{Mutex, RwLock};
 
@@ -48,9 +48,21 @@ impl Context {
             (dir, cloud, storage)
         };
 
-        let db = CloudDatabase::init_pool(&cloud)
-            .await
-            .expect("Cannot create cloud database");
+        let db = match dotenvy::var("DATABASE_STATEMENT_TIMEOUT")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+        {
+            Some(secs) => {
+                info!("database statement timeout: {}s", secs);
+                CloudDatabase::init_pool_with_timeout(
+                    ConnectOptions::new(cloud),
+                    Duration::from_secs(secs),
+                )
+                .await
+            }
+            None => CloudDatabase::init_pool(&cloud).await,
+        }
+        .expect("Cannot create cloud database");
 
         let use_bucket_storage =
             dotenvy::var("ENABLE_BUCKET_STORAGE").map_or(false, |v| v.eq("true"));
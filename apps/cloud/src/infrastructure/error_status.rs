@@ -16,6 +16,9 @@ pub enum ErrorStatus {
     BadRequest,
     Forbidden,
     Unauthorized,
+    TotpRequired,
+    Suspended,
+    TooManyRequests,
     ConflictInvitation,
     PayloadExceedsLimit(String),
 }
@@ -68,6 +71,17 @@ impl IntoResponse for ErrorStatus {
                 error_response(StatusCode::FORBIDDEN, "Sorry, you do not have permission.")
             }
             ErrorStatus::Unauthorized => error_response(StatusCode::UNAUTHORIZED, "Unauthorized."),
+            ErrorStatus::TotpRequired => error_response(
+                StatusCode::UNAUTHORIZED,
+                "A two-factor authentication code is required.",
+            ),
+            ErrorStatus::Suspended => {
+                error_response(StatusCode::FORBIDDEN, "This account has been suspended.")
+            }
+            ErrorStatus::TooManyRequests => error_response(
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many failed logins, please try again later.",
+            ),
             ErrorStatus::ConflictInvitation => {
                 error_response(StatusCode::CONFLICT, "Invitation failed.")
             }
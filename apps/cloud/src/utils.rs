@@ -59,11 +59,13 @@ pub async fn create_debug_collaboration_workspace(db: &CloudDatabase, storage: &
     let user_model1 = db
         .create_user(user1.clone())
         .await
-        .expect("failed to create user1");
+        .expect("failed to create user1")
+        .expect("user1 already exists");
     let user_model2 = db
         .create_user(user2.clone())
         .await
-        .expect("failed to create user2");
+        .expect("failed to create user2")
+        .expect("user2 already exists");
 
     let ws = db
         .create_normal_workspace(user_model1.id.clone())
@@ -82,6 +84,7 @@ pub async fn create_debug_collaboration_workspace(db: &CloudDatabase, storage: &
         .create_permission(&user_model2.email, ws.id.clone(), PermissionType::Write)
         .await
         .expect("failed to create permission")
+        .created()
         .expect("test workspace not exists");
 
     db.accept_permission(permission_id)
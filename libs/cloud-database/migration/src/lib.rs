@@ -6,6 +6,30 @@ mod m20230101_000002_create_google_user_table;
 mod m20230101_000003_create_workspaces_table;
 mod m20230101_000004_create_permissions_table;
 mod m20230217_000001_update_permissions_table;
+mod m20230301_000001_create_google_users_user_id_index;
+mod m20230320_000001_add_workspaces_deleted_at;
+mod m20230327_000001_add_workspaces_updated_at;
+mod m20230403_000001_create_permissions_indexes;
+mod m20230410_000001_create_permissions_single_owner_index;
+mod m20230417_000001_add_permissions_updated_at;
+mod m20230424_000001_add_permissions_last_accessed_at;
+mod m20230501_000001_create_permissions_workspace_id_accepted_index;
+mod m20230508_000001_lowercase_emails;
+mod m20230515_000001_add_users_timezone;
+mod m20230522_000001_create_email_changes_table;
+mod m20230529_000001_add_users_email_verified;
+mod m20230529_000002_create_verification_tokens_table;
+mod m20230605_000001_create_password_resets_table;
+mod m20230612_000001_add_users_last_login_at;
+mod m20230612_000002_create_login_events_table;
+mod m20230619_000001_create_login_attempts_table;
+mod m20230626_000001_create_workspace_audit_table;
+mod m20230703_000001_add_users_name_customized;
+mod m20230703_000002_create_user_totp_table;
+mod m20230710_000001_create_recovery_codes_table;
+mod m20230710_000002_add_users_status;
+mod m20230717_000001_add_users_is_admin;
+mod m20230724_000001_create_permissions_unique_indexes;
 
 use async_trait::async_trait;
 
@@ -20,6 +44,30 @@ impl MigratorTrait for Migrator {
             Box::new(m20230101_000003_create_workspaces_table::Migration),
             Box::new(m20230101_000004_create_permissions_table::Migration),
             Box::new(m20230217_000001_update_permissions_table::Migration),
+            Box::new(m20230301_000001_create_google_users_user_id_index::Migration),
+            Box::new(m20230320_000001_add_workspaces_deleted_at::Migration),
+            Box::new(m20230327_000001_add_workspaces_updated_at::Migration),
+            Box::new(m20230403_000001_create_permissions_indexes::Migration),
+            Box::new(m20230410_000001_create_permissions_single_owner_index::Migration),
+            Box::new(m20230417_000001_add_permissions_updated_at::Migration),
+            Box::new(m20230424_000001_add_permissions_last_accessed_at::Migration),
+            Box::new(m20230501_000001_create_permissions_workspace_id_accepted_index::Migration),
+            Box::new(m20230508_000001_lowercase_emails::Migration),
+            Box::new(m20230515_000001_add_users_timezone::Migration),
+            Box::new(m20230522_000001_create_email_changes_table::Migration),
+            Box::new(m20230529_000001_add_users_email_verified::Migration),
+            Box::new(m20230529_000002_create_verification_tokens_table::Migration),
+            Box::new(m20230605_000001_create_password_resets_table::Migration),
+            Box::new(m20230612_000001_add_users_last_login_at::Migration),
+            Box::new(m20230612_000002_create_login_events_table::Migration),
+            Box::new(m20230619_000001_create_login_attempts_table::Migration),
+            Box::new(m20230626_000001_create_workspace_audit_table::Migration),
+            Box::new(m20230703_000001_add_users_name_customized::Migration),
+            Box::new(m20230703_000002_create_user_totp_table::Migration),
+            Box::new(m20230710_000001_create_recovery_codes_table::Migration),
+            Box::new(m20230710_000002_add_users_status::Migration),
+            Box::new(m20230717_000001_add_users_is_admin::Migration),
+            Box::new(m20230724_000001_create_permissions_unique_indexes::Migration),
         ]
     }
 }
@@ -42,11 +42,18 @@ impl MigrationTrait for Migration {
 #[derive(Iden)]
 pub enum Users {
     Table,
-    Id,         // STRING PRIMARY KEY,
-    Name,       // TEXT NOT NULL,
-    Email,      // TEXT NOT NULL Unique,
-    AvatarUrl,  // TEXT,
-    TokenNonce, // SMALLINT DEFAULT 0,
-    Password,   // TEXT,
-    CreatedAt,  // TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    Id,              // STRING PRIMARY KEY,
+    Name,            // TEXT NOT NULL,
+    Email,           // TEXT NOT NULL Unique,
+    AvatarUrl,       // TEXT,
+    TokenNonce,      // SMALLINT DEFAULT 0,
+    Password,        // TEXT,
+    CreatedAt,       // TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    Timezone,        // TEXT NOT NULL DEFAULT 'UTC',
+    EmailVerified,   // BOOL NOT NULL DEFAULT FALSE,
+    LastLoginAt,     // TIMESTAMP,
+    NameCustomized,  // BOOL NOT NULL DEFAULT FALSE,
+    Status,          // SMALLINT NOT NULL DEFAULT 0,
+    SuspendedReason, // TEXT,
+    IsAdmin,         // BOOL NOT NULL DEFAULT FALSE,
 }
@@ -53,7 +53,7 @@ impl MigrationTrait for Migration {
 }
 
 #[derive(Iden)]
-enum GoogleUsers {
+pub enum GoogleUsers {
     Table,
     Id,       // STRING PRIMARY KEY,
     UserId,   // INTEGER REFERENCES users(id),
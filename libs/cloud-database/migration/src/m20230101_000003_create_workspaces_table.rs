@@ -43,4 +43,6 @@ pub enum Workspaces {
     Public,    // BOOL NOT NULL,
     Type,      // SMALLINT NOT NULL,
     CreatedAt, // TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    DeletedAt, // TIMESTAMP,
+    UpdatedAt, // TIMESTAMP,
 }
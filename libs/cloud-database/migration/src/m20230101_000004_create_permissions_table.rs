@@ -102,6 +102,8 @@ pub enum Permissions {
     Type,        // SMALLINT NOT NULL,
     Accepted,    // BOOL DEFAULT False,
     CreatedAt,   // TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UpdatedAt,   // TIMESTAMP,
+    LastAccessedAt, // TIMESTAMP,
                  // FOREIGN KEY(workspace_id) REFERENCES workspaces(id),
                  // FOREIGN KEY(user_id) REFERENCES users(id),
                  // UNIQUE (workspace_id, user_id),
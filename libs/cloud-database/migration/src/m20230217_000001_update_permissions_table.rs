@@ -23,8 +23,8 @@ impl MigrationTrait for Migration {
         let rows = trx.query_all(builder.build(&stmt)).await?;
 
         for row in rows.into_iter() {
-            let user_id = row.try_get::<String>("", "id").unwrap();
-            let user_email = row.try_get::<String>("", "email").unwrap();
+            let user_id = row.try_get::<String>("", "id")?;
+            let user_email = row.try_get::<String>("", "email")?;
 
             let stmt = Query::update()
                 .table(Permissions::Table)
@@ -0,0 +1,32 @@
+use super::m20230101_000002_create_google_user_table::GoogleUsers;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .table(GoogleUsers::Table)
+                    .name("google_users_user_id")
+                    .col(GoogleUsers::UserId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(GoogleUsers::Table)
+                    .name("google_users_user_id")
+                    .to_owned(),
+            )
+            .await
+    }
+}
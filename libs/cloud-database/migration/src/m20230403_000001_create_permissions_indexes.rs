@@ -0,0 +1,91 @@
+use super::{
+    m20220101_000001_create_user_table::Users,
+    m20230101_000004_create_permissions_table::Permissions,
+};
+use sea_orm_migration::{prelude::*, sea_orm::ConnectionTrait, sea_orm::DbBackend};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (name, col) in [
+            ("permissions_workspace_id", Permissions::WorkspaceId),
+            ("permissions_user_id", Permissions::UserId),
+        ] {
+            manager
+                .create_index(
+                    Index::create()
+                        .table(Permissions::Table)
+                        .name(name)
+                        .col(col)
+                        .if_not_exists()
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // partial and expression indexes are not expressible with sea-query
+        let db = manager.get_connection();
+        match manager.get_database_backend() {
+            DbBackend::Postgres => {
+                db.execute_unprepared(
+                    "CREATE INDEX IF NOT EXISTS permissions_user_email ON permissions (user_email) \
+                     WHERE user_email IS NOT NULL",
+                )
+                .await?;
+                db.execute_unprepared(
+                    "CREATE INDEX IF NOT EXISTS users_email_lower ON users (lower(email))",
+                )
+                .await?;
+            }
+            DbBackend::Sqlite => {
+                db.execute_unprepared(
+                    "CREATE INDEX IF NOT EXISTS permissions_user_email ON permissions (user_email)",
+                )
+                .await?;
+                db.execute_unprepared(
+                    "CREATE INDEX IF NOT EXISTS users_email_lower ON users (lower(email))",
+                )
+                .await?;
+            }
+            DbBackend::MySql => {
+                // TEXT columns need a prefix length, expression indexes need MySQL 8.0.13
+                db.execute_unprepared(
+                    "CREATE INDEX permissions_user_email ON permissions (user_email(255))",
+                )
+                .await?;
+                db.execute_unprepared("CREATE INDEX users_email_lower ON users ((lower(email)))")
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for name in [
+            "permissions_workspace_id",
+            "permissions_user_id",
+            "permissions_user_email",
+        ] {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .table(Permissions::Table)
+                        .name(name)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Users::Table)
+                    .name("users_email_lower")
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::{prelude::*, sea_orm::ConnectionTrait, sea_orm::DbBackend};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 99 is `PermissionType::Owner`, a workspace has exactly one owner.
+        // MySQL has no partial indexes, there the invariant is only kept by
+        // the application.
+        match manager.get_database_backend() {
+            DbBackend::Postgres | DbBackend::Sqlite => {
+                manager
+                    .get_connection()
+                    .execute_unprepared(
+                        "CREATE UNIQUE INDEX IF NOT EXISTS permissions_workspace_id_owner \
+                         ON permissions (workspace_id) WHERE type = 99",
+                    )
+                    .await?;
+            }
+            DbBackend::MySql => {}
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        match manager.get_database_backend() {
+            DbBackend::Postgres | DbBackend::Sqlite => {
+                manager
+                    .get_connection()
+                    .execute_unprepared("DROP INDEX IF EXISTS permissions_workspace_id_owner")
+                    .await?;
+            }
+            DbBackend::MySql => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use super::m20230101_000004_create_permissions_table::Permissions;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Permissions::Table)
+                    .add_column(
+                        ColumnDef::new(Permissions::LastAccessedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Permissions::Table)
+                    .drop_column(Permissions::LastAccessedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
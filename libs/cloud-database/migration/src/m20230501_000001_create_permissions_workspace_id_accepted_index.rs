@@ -0,0 +1,53 @@
+use super::m20230101_000004_create_permissions_table::Permissions;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // member counts and read checks filter on both, lookups by workspace
+        // alone use the prefix, replacing `permissions_workspace_id`
+        manager
+            .create_index(
+                Index::create()
+                    .table(Permissions::Table)
+                    .name("permissions_workspace_id_accepted")
+                    .col(Permissions::WorkspaceId)
+                    .col(Permissions::Accepted)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Permissions::Table)
+                    .name("permissions_workspace_id")
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .table(Permissions::Table)
+                    .name("permissions_workspace_id")
+                    .col(Permissions::WorkspaceId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Permissions::Table)
+                    .name("permissions_workspace_id_accepted")
+                    .to_owned(),
+            )
+            .await
+    }
+}
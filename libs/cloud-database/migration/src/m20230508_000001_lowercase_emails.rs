@@ -0,0 +1,123 @@
+use crate::m20220101_000001_create_user_table::Users;
+use crate::m20230101_000004_create_permissions_table::Permissions;
+
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::query::*;
+use std::collections::{HashMap, HashSet};
+
+/// Lowercase the emails written before they were normalized on insert.
+///
+/// Accounts whose emails only differ by case are left as they are, lookups
+/// return the oldest of them. Of the pending invitations to the same email
+/// in a workspace only the oldest is kept, and invitations to an email that
+/// signed up since are handed to that user like on signup.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let builder = db.get_database_backend();
+
+        let trx = db.begin().await?;
+
+        let stmt = Query::select()
+            .column(Users::Id)
+            .column(Users::Email)
+            .from(Users::Table)
+            .order_by(Users::CreatedAt, Order::Asc)
+            .order_by(Users::Id, Order::Asc)
+            .to_owned();
+        let mut users = HashMap::<String, Vec<(String, String)>>::new();
+        for row in trx.query_all(builder.build(&stmt)).await? {
+            let id = row.try_get::<String>("", "id")?;
+            let email = row.try_get::<String>("", "email")?;
+            users
+                .entry(email.to_lowercase())
+                .or_default()
+                .push((id, email));
+        }
+        for (lower, accounts) in &users {
+            if let [(id, email)] = &accounts[..] {
+                if email != lower {
+                    let stmt = Query::update()
+                        .table(Users::Table)
+                        .values(vec![(Users::Email, lower.clone().into())])
+                        .and_where(Expr::col(Users::Id).eq(id.clone()))
+                        .to_owned();
+                    trx.execute(builder.build(&stmt)).await?;
+                }
+            }
+        }
+
+        let stmt = Query::select()
+            .column(Permissions::Id)
+            .column(Permissions::WorkspaceId)
+            .column(Permissions::UserEmail)
+            .from(Permissions::Table)
+            .and_where(Expr::col(Permissions::UserId).is_null())
+            .and_where(Expr::col(Permissions::UserEmail).is_not_null())
+            .order_by(Permissions::CreatedAt, Order::Asc)
+            .order_by(Permissions::Id, Order::Asc)
+            .to_owned();
+        let mut invited = HashSet::new();
+        for row in trx.query_all(builder.build(&stmt)).await? {
+            let id = row.try_get::<String>("", "id")?;
+            let workspace_id = row.try_get::<String>("", "workspace_id")?;
+            let email = row.try_get::<String>("", "user_email")?;
+            let lower = email.to_lowercase();
+
+            let user_id = users
+                .get(&lower)
+                .and_then(|accounts| accounts.first())
+                .map(|(id, _)| id.clone());
+            let member = match &user_id {
+                Some(user_id) => {
+                    let stmt = Query::select()
+                        .column(Permissions::Id)
+                        .from(Permissions::Table)
+                        .and_where(Expr::col(Permissions::WorkspaceId).eq(workspace_id.clone()))
+                        .and_where(Expr::col(Permissions::UserId).eq(user_id.clone()))
+                        .to_owned();
+                    trx.query_one(builder.build(&stmt)).await?.is_some()
+                }
+                None => false,
+            };
+
+            if member || !invited.insert((workspace_id, lower.clone())) {
+                let stmt = Query::delete()
+                    .from_table(Permissions::Table)
+                    .and_where(Expr::col(Permissions::Id).eq(id))
+                    .to_owned();
+                trx.execute(builder.build(&stmt)).await?;
+            } else if let Some(user_id) = user_id {
+                let stmt = Query::update()
+                    .table(Permissions::Table)
+                    .values(vec![
+                        (Permissions::UserId, user_id.into()),
+                        (Permissions::UserEmail, Option::<String>::None.into()),
+                    ])
+                    .and_where(Expr::col(Permissions::Id).eq(id))
+                    .to_owned();
+                trx.execute(builder.build(&stmt)).await?;
+            } else if email != lower {
+                let stmt = Query::update()
+                    .table(Permissions::Table)
+                    .values(vec![(Permissions::UserEmail, lower.into())])
+                    .and_where(Expr::col(Permissions::Id).eq(id))
+                    .to_owned();
+                trx.execute(builder.build(&stmt)).await?;
+            }
+        }
+
+        trx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // the original casing is gone, lowercased emails work either way
+        Ok(())
+    }
+}
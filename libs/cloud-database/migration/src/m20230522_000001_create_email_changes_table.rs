@@ -0,0 +1,66 @@
+use super::m20220101_000001_create_user_table::Users;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EmailChanges::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EmailChanges::UserId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(EmailChanges::NewEmail).string().not_null())
+                    .col(
+                        ColumnDef::new(EmailChanges::TokenHash)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(EmailChanges::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmailChanges::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("email_changes_user_id_fkey")
+                            .from(EmailChanges::Table, EmailChanges::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EmailChanges::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum EmailChanges {
+    Table,
+    UserId,    // STRING PRIMARY KEY REFERENCES users(id),
+    NewEmail,  // TEXT NOT NULL,
+    TokenHash, // TEXT NOT NULL UNIQUE,
+    ExpiresAt, // TIMESTAMP NOT NULL,
+    CreatedAt, // TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+}
@@ -0,0 +1,73 @@
+use super::m20220101_000001_create_user_table::Users;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(VerificationTokens::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VerificationTokens::TokenHash)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationTokens::UserId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationTokens::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationTokens::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("verification_tokens_user_id_fkey")
+                            .from(VerificationTokens::Table, VerificationTokens::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .table(VerificationTokens::Table)
+                    .name("verification_tokens_user_id")
+                    .col(VerificationTokens::UserId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(VerificationTokens::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum VerificationTokens {
+    Table,
+    TokenHash, // TEXT PRIMARY KEY,
+    UserId,    // STRING NOT NULL REFERENCES users(id),
+    ExpiresAt, // TIMESTAMP NOT NULL,
+    CreatedAt, // TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+}
@@ -0,0 +1,87 @@
+use super::m20220101_000001_create_user_table::Users;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoginEvents::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LoginEvents::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LoginEvents::UserId).string().not_null())
+                    .col(
+                        ColumnDef::new(LoginEvents::Method)
+                            .small_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LoginEvents::Ip).string())
+                    .col(ColumnDef::new(LoginEvents::UserAgent).string())
+                    .col(
+                        ColumnDef::new(LoginEvents::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("login_events_user_id_fkey")
+                            .from(LoginEvents::Table, LoginEvents::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // the history of a user newest first
+        manager
+            .create_index(
+                Index::create()
+                    .table(LoginEvents::Table)
+                    .name("login_events_user_id_created_at")
+                    .col(LoginEvents::UserId)
+                    .col(LoginEvents::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+        // pruning
+        manager
+            .create_index(
+                Index::create()
+                    .table(LoginEvents::Table)
+                    .name("login_events_created_at")
+                    .col(LoginEvents::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LoginEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum LoginEvents {
+    Table,
+    Id,        // STRING PRIMARY KEY,
+    UserId,    // STRING NOT NULL REFERENCES users(id),
+    Method,    // SMALLINT NOT NULL,
+    Ip,        // TEXT,
+    UserAgent, // TEXT,
+    CreatedAt, // TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+}
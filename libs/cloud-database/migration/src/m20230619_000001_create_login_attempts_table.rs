@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+/// Failed logins per email, deliberately not referencing `users` so emails
+/// without an account are throttled the same way.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoginAttempts::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LoginAttempts::Email)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(LoginAttempts::Count)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(LoginAttempts::WindowStart)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LoginAttempts::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum LoginAttempts {
+    Table,
+    Email,       // TEXT PRIMARY KEY,
+    Count,       // INTEGER NOT NULL DEFAULT 0,
+    WindowStart, // TIMESTAMP NOT NULL,
+}
@@ -0,0 +1,100 @@
+use super::m20220101_000001_create_user_table::Users;
+use super::m20230101_000003_create_workspaces_table::Workspaces;
+use sea_orm_migration::prelude::*;
+
+/// Changes to workspace settings. Entries go with their workspace but outlive
+/// the user who made them, whose id is then cleared.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WorkspaceAudit::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WorkspaceAudit::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WorkspaceAudit::WorkspaceId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WorkspaceAudit::ActorUserId).string())
+                    .col(ColumnDef::new(WorkspaceAudit::Action).string().not_null())
+                    .col(ColumnDef::new(WorkspaceAudit::OldValue).string())
+                    .col(ColumnDef::new(WorkspaceAudit::NewValue).string())
+                    .col(
+                        ColumnDef::new(WorkspaceAudit::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("workspace_audit_workspace_id_fkey")
+                            .from(WorkspaceAudit::Table, WorkspaceAudit::WorkspaceId)
+                            .to(Workspaces::Table, Workspaces::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("workspace_audit_actor_user_id_fkey")
+                            .from(WorkspaceAudit::Table, WorkspaceAudit::ActorUserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // the history of a workspace in order
+        manager
+            .create_index(
+                Index::create()
+                    .table(WorkspaceAudit::Table)
+                    .name("workspace_audit_workspace_id_created_at")
+                    .col(WorkspaceAudit::WorkspaceId)
+                    .col(WorkspaceAudit::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+        // clearing the actor when a user is deleted
+        manager
+            .create_index(
+                Index::create()
+                    .table(WorkspaceAudit::Table)
+                    .name("workspace_audit_actor_user_id")
+                    .col(WorkspaceAudit::ActorUserId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WorkspaceAudit::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum WorkspaceAudit {
+    Table,
+    Id,          // STRING PRIMARY KEY,
+    WorkspaceId, // STRING NOT NULL REFERENCES workspaces(id),
+    ActorUserId, // STRING REFERENCES users(id),
+    Action,      // TEXT NOT NULL,
+    OldValue,    // TEXT,
+    NewValue,    // TEXT,
+    CreatedAt,   // TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+}
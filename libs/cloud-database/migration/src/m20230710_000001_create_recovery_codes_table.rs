@@ -0,0 +1,66 @@
+use super::m20220101_000001_create_user_table::Users;
+use sea_orm_migration::prelude::*;
+
+/// One-time codes logging in when the authenticator app is lost. Only the
+/// SHA-256 of each code is stored; a used code keeps its row until the set is
+/// replaced, so the user can see how many are left.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecoveryCodes::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RecoveryCodes::UserId).string().not_null())
+                    .col(ColumnDef::new(RecoveryCodes::CodeHash).string().not_null())
+                    .col(
+                        ColumnDef::new(RecoveryCodes::Used)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(RecoveryCodes::UsedAt).timestamp_with_time_zone())
+                    .col(
+                        ColumnDef::new(RecoveryCodes::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(RecoveryCodes::UserId)
+                            .col(RecoveryCodes::CodeHash),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("recovery_codes_user_id_fkey")
+                            .from(RecoveryCodes::Table, RecoveryCodes::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecoveryCodes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum RecoveryCodes {
+    Table,
+    UserId,    // STRING NOT NULL REFERENCES users(id),
+    CodeHash,  // TEXT NOT NULL, PRIMARY KEY (user_id, code_hash)
+    Used,      // BOOL NOT NULL DEFAULT FALSE,
+    UsedAt,    // TIMESTAMP,
+    CreatedAt, // TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+}
@@ -0,0 +1,99 @@
+use crate::m20230101_000004_create_permissions_table::Permissions;
+
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{query::*, DbBackend};
+use std::collections::HashSet;
+
+/// A user or email is invited to a workspace at most once.
+///
+/// Duplicates written before are resolved first: the row with the highest
+/// permission is kept, then the accepted one, then the oldest.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let builder = db.get_database_backend();
+
+        let trx = db.begin().await?;
+
+        let stmt = Query::select()
+            .column(Permissions::Id)
+            .column(Permissions::WorkspaceId)
+            .column(Permissions::UserId)
+            .column(Permissions::UserEmail)
+            .from(Permissions::Table)
+            .order_by(Permissions::Type, Order::Desc)
+            .order_by(Permissions::Accepted, Order::Desc)
+            .order_by(Permissions::CreatedAt, Order::Asc)
+            .order_by(Permissions::Id, Order::Asc)
+            .to_owned();
+        let (mut users, mut emails) = (HashSet::new(), HashSet::new());
+        for row in trx.query_all(builder.build(&stmt)).await? {
+            let id = row.try_get::<String>("", "id")?;
+            let workspace_id = row.try_get::<String>("", "workspace_id")?;
+            let user_id = row.try_get::<Option<String>>("", "user_id")?;
+            let email = row.try_get::<Option<String>>("", "user_email")?;
+
+            let user = user_id.map(|user_id| (workspace_id.clone(), user_id));
+            let email = email.map(|email| (workspace_id, email));
+            let duplicate = user.as_ref().is_some_and(|user| users.contains(user))
+                || email.as_ref().is_some_and(|email| emails.contains(email));
+            if duplicate {
+                let stmt = Query::delete()
+                    .from_table(Permissions::Table)
+                    .and_where(Expr::col(Permissions::Id).eq(id))
+                    .to_owned();
+                trx.execute(builder.build(&stmt)).await?;
+            } else {
+                users.extend(user);
+                emails.extend(email);
+            }
+        }
+
+        // NULLs are distinct, invitations by email and memberships by user
+        // only collide with their own kind
+        let email_column = match builder {
+            // TEXT columns need a prefix length
+            DbBackend::MySql => "user_email(255)",
+            DbBackend::Postgres | DbBackend::Sqlite => "user_email",
+        };
+        let if_not_exists = match builder {
+            DbBackend::MySql => "",
+            DbBackend::Postgres | DbBackend::Sqlite => "IF NOT EXISTS ",
+        };
+        trx.execute_unprepared(&format!(
+            "CREATE UNIQUE INDEX {if_not_exists}permissions_workspace_id_user_id_unique \
+             ON permissions (workspace_id, user_id)"
+        ))
+        .await?;
+        trx.execute_unprepared(&format!(
+            "CREATE UNIQUE INDEX {if_not_exists}permissions_workspace_id_user_email_unique \
+             ON permissions (workspace_id, {email_column})"
+        ))
+        .await?;
+
+        trx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for name in [
+            "permissions_workspace_id_user_id_unique",
+            "permissions_workspace_id_user_email_unique",
+        ] {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .table(Permissions::Table)
+                        .name(name)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+//! Password and token hashing helpers.
+//!
+//! Passwords are stored as argon2id PHC strings (`$argon2id$v=19$...`).
+//! Rows written before hashing was introduced still hold the plaintext
+//! password, `CloudDatabase::user_login` replaces it with a hash on the
+//! next successful login.
+//!
+//! Tokens mailed to users are random, so a plain SHA-256 of them is enough
+//! to keep a database leak from handing them out, and can be looked up.
+use argon2::{
+    password_hash::{
+        rand_core::{OsRng, RngCore},
+        PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+    },
+    Algorithm, Argon2, Params, Version,
+};
+use sha2::{Digest, Sha256};
+
+pub use argon2::password_hash::Error as PasswordHashError;
+
+/// Shortest password accepted by `CloudDatabase::change_password`.
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Cost of new argon2id hashes. Existing hashes keep verifying after a
+/// change, their PHC string records the parameters they were made with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordParams {
+    /// memory in KiB
+    pub memory_cost: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for PasswordParams {
+    /// The OWASP recommended minimum, also the argon2 crate defaults.
+    fn default() -> Self {
+        Self {
+            memory_cost: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// Hash a plaintext password with a freshly generated salt.
+pub fn hash_password(plain: &str) -> Result<String, PasswordHashError> {
+    hash_password_with(plain, &PasswordParams::default())
+}
+
+/// Like [`hash_password`] with explicit costs, fails when they are out of
+/// the range argon2 accepts.
+pub fn hash_password_with(
+    plain: &str,
+    params: &PasswordParams,
+) -> Result<String, PasswordHashError> {
+    let params = Params::new(
+        params.memory_cost,
+        params.iterations,
+        params.parallelism,
+        None,
+    )?;
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password(plain.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+}
+
+/// Check a plaintext password against a stored hash.
+///
+/// Returns `false` if the stored value is not a valid PHC string.
+pub fn verify_password(plain: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|hash| {
+            Argon2::default()
+                .verify_password(plain.as_bytes(), &hash)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// Whether a stored password is a PHC string rather than a legacy plaintext.
+pub fn is_password_hash(stored: &str) -> bool {
+    PasswordHash::new(stored).is_ok()
+}
+
+/// Compare against a legacy plaintext password, in time independent of
+/// where the two differ.
+pub(crate) fn verify_legacy_password(plain: &str, stored: &str) -> bool {
+    plain.len() == stored.len()
+        && plain
+            .bytes()
+            .zip(stored.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// A random token to send by mail, stored only as its [`hash_token`].
+pub(crate) fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex(&bytes)
+}
+
+pub(crate) fn hash_token(token: &str) -> String {
+    hex(&Sha256::digest(token.as_bytes()))
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn password_hash_round_trip() {
+        let hash = hash_password("password").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("password", &hash));
+        assert!(!verify_password("wrong", &hash));
+
+        // salted, so hashing twice gives different results
+        assert_ne!(hash, hash_password("password").unwrap());
+        // legacy plaintext values never verify
+        assert!(!verify_password("password", "password"));
+        assert!(is_password_hash(&hash));
+        assert!(!is_password_hash("password"));
+    }
+
+    #[test]
+    fn password_legacy() {
+        assert!(verify_legacy_password("password", "password"));
+        assert!(!verify_legacy_password("password", "passworD"));
+        assert!(!verify_legacy_password("password", "password1"));
+        assert!(!verify_legacy_password("", "password"));
+    }
+
+    #[test]
+    fn password_hash_params() {
+        let params = PasswordParams {
+            memory_cost: 8 * 1024,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let hash = hash_password_with("password", &params).unwrap();
+        assert!(hash.starts_with("$argon2id$v=19$m=8192,t=1,p=1$"));
+        // verified with the parameters stored in the hash
+        assert!(verify_password("password", &hash));
+
+        assert!(hash_password_with(
+            "password",
+            &PasswordParams {
+                iterations: 0,
+                ..params
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn token_hash() {
+        let token = new_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, new_token());
+
+        let hash = hash_token(&token);
+        assert_eq!(hash, hash_token(&token));
+        assert_ne!(hash, token);
+        assert_eq!(
+            hash_token(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
@@ -2,7 +2,15 @@
 
 pub mod prelude;
 
+pub mod email_changes;
 pub mod google_users;
+pub mod login_attempts;
+pub mod login_events;
+pub mod password_resets;
 pub mod permissions;
+pub mod recovery_codes;
+pub mod user_totp;
 pub mod users;
+pub mod verification_tokens;
+pub mod workspace_audit;
 pub mod workspaces;
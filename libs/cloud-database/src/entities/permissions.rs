@@ -14,6 +14,8 @@ pub struct Model {
     pub r#type: i16,
     pub accepted: bool,
     pub created_at: Option<DateTimeWithTimeZone>,
+    pub updated_at: Option<DateTimeWithTimeZone>,
+    pub last_accessed_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,6 +1,14 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.7
 
+pub use super::email_changes::Entity as EmailChanges;
 pub use super::google_users::Entity as GoogleUsers;
+pub use super::login_attempts::Entity as LoginAttempts;
+pub use super::login_events::Entity as LoginEvents;
+pub use super::password_resets::Entity as PasswordResets;
 pub use super::permissions::Entity as Permissions;
+pub use super::recovery_codes::Entity as RecoveryCodes;
+pub use super::user_totp::Entity as UserTotp;
 pub use super::users::Entity as Users;
+pub use super::verification_tokens::Entity as VerificationTokens;
+pub use super::workspace_audit::Entity as WorkspaceAudit;
 pub use super::workspaces::Entity as Workspaces;
@@ -14,14 +14,41 @@ pub struct Model {
     pub token_nonce: Option<i16>,
     pub password: Option<String>,
     pub created_at: Option<DateTimeWithTimeZone>,
+    pub timezone: String,
+    pub email_verified: bool,
+    pub last_login_at: Option<DateTimeWithTimeZone>,
+    pub name_customized: bool,
+    pub status: i16,
+    pub suspended_reason: Option<String>,
+    pub is_admin: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_one = "super::email_changes::Entity")]
+    EmailChanges,
     #[sea_orm(has_many = "super::google_users::Entity")]
     GoogleUsers,
+    #[sea_orm(has_many = "super::login_events::Entity")]
+    LoginEvents,
+    #[sea_orm(has_one = "super::password_resets::Entity")]
+    PasswordResets,
     #[sea_orm(has_many = "super::permissions::Entity")]
     Permissions,
+    #[sea_orm(has_many = "super::recovery_codes::Entity")]
+    RecoveryCodes,
+    #[sea_orm(has_one = "super::user_totp::Entity")]
+    UserTotp,
+    #[sea_orm(has_many = "super::verification_tokens::Entity")]
+    VerificationTokens,
+    #[sea_orm(has_many = "super::workspace_audit::Entity")]
+    WorkspaceAudit,
+}
+
+impl Related<super::email_changes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EmailChanges.def()
+    }
 }
 
 impl Related<super::google_users::Entity> for Entity {
@@ -30,10 +57,46 @@ impl Related<super::google_users::Entity> for Entity {
     }
 }
 
+impl Related<super::login_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LoginEvents.def()
+    }
+}
+
+impl Related<super::password_resets::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasswordResets.def()
+    }
+}
+
 impl Related<super::permissions::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Permissions.def()
     }
 }
 
+impl Related<super::recovery_codes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecoveryCodes.def()
+    }
+}
+
+impl Related<super::user_totp::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserTotp.def()
+    }
+}
+
+impl Related<super::verification_tokens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VerificationTokens.def()
+    }
+}
+
+impl Related<super::workspace_audit::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WorkspaceAudit.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -10,12 +10,16 @@ pub struct Model {
     pub public: bool,
     pub r#type: i16,
     pub created_at: Option<DateTimeWithTimeZone>,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+    pub updated_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::permissions::Entity")]
     Permissions,
+    #[sea_orm(has_many = "super::workspace_audit::Entity")]
+    WorkspaceAudit,
 }
 
 impl Related<super::permissions::Entity> for Entity {
@@ -24,4 +28,10 @@ impl Related<super::permissions::Entity> for Entity {
     }
 }
 
+impl Related<super::workspace_audit::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WorkspaceAudit.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,183 @@
+use super::{model::UserCred, types::StorageResult, CloudDatabase};
+use futures::stream::{self, BoxStream, StreamExt};
+use jwst_logger::warn;
+#[cfg(feature = "postgres")]
+use sea_orm::{ConnectionTrait, DbBackend, DbErr, RuntimeErr, Statement};
+use serde::{Deserialize, Serialize};
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Arc, RwLock},
+};
+use tokio::sync::broadcast;
+
+/// Postgres channel the events are sent on with `pg_notify`.
+#[cfg(feature = "postgres")]
+const CHANNEL: &str = "cloud_database_permissions";
+/// Events kept for subscribers that fall behind, older ones are skipped.
+pub(super) const CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionEventKind {
+    Accepted,
+    /// the permission type changed, e.g. by a transfer of ownership
+    Changed,
+    Removed,
+}
+
+/// A change to who can access a workspace, see
+/// [`CloudDatabase::subscribe_permission_events`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionEvent {
+    pub workspace_id: String,
+    /// `None` for invitations sent to an email that hasn't signed up
+    pub user_id: Option<String>,
+    pub kind: PermissionEventKind,
+}
+
+/// A committed change, passed to the hooks of [`CloudDatabase::on_mutation`].
+#[derive(Debug, Clone)]
+pub enum MutationEvent {
+    UserCreated {
+        user_id: String,
+    },
+    UserDeleted {
+        user_id: String,
+    },
+    WorkspaceCreated {
+        workspace_id: String,
+    },
+    WorkspaceUpdated {
+        workspace_id: String,
+    },
+    /// deleted or soft deleted
+    WorkspaceDeleted {
+        workspace_id: String,
+    },
+    WorkspaceRestored {
+        workspace_id: String,
+    },
+    PermissionCreated {
+        workspace_id: String,
+        user: UserCred,
+    },
+    /// accepted, changed or removed, the event of
+    /// [`CloudDatabase::subscribe_permission_events`]
+    Permission(PermissionEvent),
+}
+
+pub type MutationHook = Box<dyn Fn(MutationEvent) + Send + Sync>;
+
+pub(super) type MutationHooks = Arc<RwLock<Vec<Arc<dyn Fn(MutationEvent) + Send + Sync>>>>;
+
+impl CloudDatabase {
+    /// Call `hook` after every change made through this database and its
+    /// clones has been committed, e.g. to invalidate a cache.
+    ///
+    /// Hooks run in order on the task that made the change, so they should
+    /// be quick. A panicking hook is logged and doesn't affect the others.
+    /// Changes made through the `*_tx` methods are not reported, the caller
+    /// owns their transaction.
+    pub fn on_mutation(&self, hook: MutationHook) {
+        self.hooks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::from(hook));
+    }
+
+    pub(super) fn mutated(&self, event: MutationEvent) {
+        // not held while the hooks run, they may register other hooks
+        let hooks = self.hooks.read().unwrap_or_else(|e| e.into_inner()).clone();
+        for hook in hooks {
+            if catch_unwind(AssertUnwindSafe(|| hook(event.clone()))).is_err() {
+                warn!("mutation hook panicked on {:?}", event);
+            }
+        }
+    }
+
+    /// Events for every permission accepted, changed or removed from now on.
+    ///
+    /// On Postgres they come through `LISTEN` on a dedicated connection, so
+    /// changes made by other processes are seen too. Other backends only see
+    /// the changes made through this `CloudDatabase` and its clones.
+    pub async fn subscribe_permission_events(
+        &self,
+    ) -> StorageResult<BoxStream<'static, PermissionEvent>> {
+        #[cfg(feature = "postgres")]
+        if self.pool.get_database_backend() == DbBackend::Postgres {
+            return self.listen_postgres().await;
+        }
+
+        let receiver = self.events.subscribe();
+        Ok(stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("permission events lagged, {} skipped", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed())
+    }
+
+    #[cfg(feature = "postgres")]
+    async fn listen_postgres(&self) -> StorageResult<BoxStream<'static, PermissionEvent>> {
+        use sqlx::postgres::PgListener;
+
+        let listen = async {
+            let mut listener =
+                PgListener::connect_with(self.pool.get_postgres_connection_pool()).await?;
+            listener.listen(CHANNEL).await?;
+            Ok(listener)
+        };
+        let listener = listen
+            .await
+            .map_err(|e| DbErr::Conn(RuntimeErr::SqlxError(e)))?;
+        Ok(listener
+            .into_stream()
+            .filter_map(|notification| async move {
+                match notification {
+                    Ok(notification) => serde_json::from_str(notification.payload())
+                        .map_err(|e| warn!("invalid permission event: {}", e))
+                        .ok(),
+                    Err(e) => {
+                        warn!("permission events listener failed: {}", e);
+                        None
+                    }
+                }
+            })
+            .boxed())
+    }
+
+    /// Tell the subscribers about a committed change. The change stands even
+    /// when this fails, so errors are only logged.
+    pub(super) async fn publish_permission_event(&self, event: PermissionEvent) {
+        self.mutated(MutationEvent::Permission(event.clone()));
+
+        #[cfg(feature = "postgres")]
+        if self.pool.get_database_backend() == DbBackend::Postgres {
+            let notify = match serde_json::to_string(&event) {
+                Ok(payload) => {
+                    self.pool
+                        .execute(Statement::from_sql_and_values(
+                            DbBackend::Postgres,
+                            "SELECT pg_notify($1, $2)",
+                            [CHANNEL.into(), payload.into()],
+                        ))
+                        .await
+                }
+                Err(e) => Err(DbErr::Custom(e.to_string())),
+            };
+            if let Err(e) = notify {
+                warn!("failed to notify permission event: {}", e);
+            }
+            return;
+        }
+
+        // no receiver is not an error, nobody is listening
+        let _ = self.events.send(event);
+    }
+}
@@ -1,10 +1,44 @@
+#[cfg(not(any(feature = "mysql", feature = "postgres", feature = "sqlite")))]
+compile_error!("at least one of the `mysql`, `postgres` or `sqlite` features must be enabled");
+
+pub mod crypto;
 #[forbid(unsafe_code)]
 mod database;
 mod entities;
+mod events;
+#[cfg(feature = "testing")]
+mod memory;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod model;
+mod pool;
+mod retry;
+mod schema;
+mod slow_query;
+mod storage;
+#[cfg(any(test, feature = "test-utils"))]
+mod test_utils;
+mod throttle;
+mod timeout;
+mod totp;
+mod types;
 
 pub use database::CloudDatabase;
+pub use events::{MutationEvent, MutationHook, PermissionEvent, PermissionEventKind};
+#[cfg(feature = "testing")]
+pub use memory::MemoryStorage;
 pub use model::*;
+pub use pool::PoolStats;
+pub use retry::RetryPolicy;
+pub use schema::SchemaReport;
+pub use sea_orm::ConnectOptions;
+pub use storage::DbStorage;
+#[cfg(any(test, feature = "test-utils"))]
+pub use test_utils::{fixtures, TestDatabase};
+pub use throttle::LoginThrottle;
+pub use timeout::QueryOptions;
+pub use totp::TotpKey;
+pub use types::{StorageError, StorageResult};
 
 use entities::prelude::*;
 use sea_orm::EntityTrait;
@@ -18,6 +52,24 @@ type WorkspacesColumn = <Workspaces as EntityTrait>::Column;
 type PermissionModel = <Permissions as EntityTrait>::Model;
 type PermissionActiveModel = entities::permissions::ActiveModel;
 type PermissionColumn = <Permissions as EntityTrait>::Column;
-type GoogleUsersModel = <GoogleUsers as EntityTrait>::Model;
 type GoogleUsersActiveModel = entities::google_users::ActiveModel;
 type GoogleUsersColumn = <GoogleUsers as EntityTrait>::Column;
+type LoginEventsModel = <LoginEvents as EntityTrait>::Model;
+type LoginEventsActiveModel = entities::login_events::ActiveModel;
+type LoginEventsColumn = <LoginEvents as EntityTrait>::Column;
+type LoginAttemptsActiveModel = entities::login_attempts::ActiveModel;
+type LoginAttemptsColumn = <LoginAttempts as EntityTrait>::Column;
+type EmailChangesActiveModel = entities::email_changes::ActiveModel;
+type EmailChangesColumn = <EmailChanges as EntityTrait>::Column;
+type PasswordResetsActiveModel = entities::password_resets::ActiveModel;
+type PasswordResetsColumn = <PasswordResets as EntityTrait>::Column;
+type RecoveryCodesActiveModel = entities::recovery_codes::ActiveModel;
+type RecoveryCodesColumn = <RecoveryCodes as EntityTrait>::Column;
+type UserTotpModel = <UserTotp as EntityTrait>::Model;
+type UserTotpActiveModel = entities::user_totp::ActiveModel;
+type UserTotpColumn = <UserTotp as EntityTrait>::Column;
+type VerificationTokensActiveModel = entities::verification_tokens::ActiveModel;
+type VerificationTokensColumn = <VerificationTokens as EntityTrait>::Column;
+type WorkspaceAuditModel = <WorkspaceAudit as EntityTrait>::Model;
+type WorkspaceAuditActiveModel = entities::workspace_audit::ActiveModel;
+type WorkspaceAuditColumn = <WorkspaceAudit as EntityTrait>::Column;
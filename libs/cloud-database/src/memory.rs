@@ -0,0 +1,377 @@
+use super::{
+    crypto::{hash_password, verify_password},
+    model::{
+        validate_email, CreatePermissionOutcome, CreateUser, Permission, PermissionType,
+        UpdateWorkspace, User, UserCred, UserLogin, UserStatus, Workspace, WorkspaceDetail,
+        WorkspaceType, WorkspaceWithPermission, DEFAULT_TIMEZONE,
+    },
+    storage::DbStorage,
+    types::{StorageError, StorageResult},
+    PermissionModel, UsersModel,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use nanoid::nanoid;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Default)]
+struct State {
+    users: Vec<UsersModel>,
+    workspaces: Vec<Workspace>,
+    permissions: Vec<PermissionModel>,
+}
+
+/// In-memory [`DbStorage`] for tests of the layers above the database.
+///
+/// Keeps the invariants of the real schema: unique emails, a single owner
+/// per workspace and permissions removed along with their workspace.
+#[derive(Default)]
+pub struct MemoryStorage {
+    state: Mutex<State>,
+}
+
+impl MemoryStorage {
+    fn state(&self) -> MutexGuard<'_, State> {
+        // a panicking test must not poison the storage of the others
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn user(model: &UsersModel) -> User {
+    User {
+        id: model.id.clone(),
+        name: model.name.clone(),
+        email: model.email.clone(),
+        avatar_url: model.avatar_url.clone(),
+        created_at: model.created_at.unwrap_or_default().into(),
+        timezone: model.timezone.clone(),
+        email_verified: model.email_verified,
+    }
+}
+
+fn permission(model: &PermissionModel) -> Permission {
+    Permission {
+        id: model.id.clone(),
+        r#type: model.r#type.into(),
+        workspace_id: model.workspace_id.clone(),
+        user_id: model.user_id.clone(),
+        user_email: model.user_email.clone(),
+        accepted: model.accepted,
+        created_at: model.created_at.unwrap_or_default().into(),
+        updated_at: model.updated_at.map(Into::into),
+    }
+}
+
+impl State {
+    fn user_by_email(&self, email: &str) -> Option<&UsersModel> {
+        self.users
+            .iter()
+            .find(|u| u.email.eq_ignore_ascii_case(email))
+    }
+
+    fn normal_workspace(&self, workspace_id: &str) -> Option<&Workspace> {
+        self.workspaces
+            .iter()
+            .find(|w| w.id == workspace_id && w.r#type == WorkspaceType::Normal)
+    }
+
+    fn insert_permission(
+        &mut self,
+        workspace_id: String,
+        user_id: Option<String>,
+        user_email: Option<String>,
+        permission_type: PermissionType,
+        accepted: bool,
+    ) -> StorageResult<String> {
+        if permission_type == PermissionType::Owner
+            && self
+                .permissions
+                .iter()
+                .any(|p| p.workspace_id == workspace_id && p.r#type == PermissionType::Owner as i16)
+        {
+            return Err(StorageError::Conflict);
+        }
+        let id = nanoid!();
+        let now = Utc::now().into();
+        self.permissions.push(PermissionModel {
+            id: id.clone(),
+            workspace_id,
+            user_id,
+            user_email,
+            r#type: permission_type as i16,
+            accepted,
+            created_at: Some(now),
+            updated_at: Some(now),
+            last_accessed_at: None,
+        });
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl DbStorage for MemoryStorage {
+    async fn create_user(&self, new_user: CreateUser) -> StorageResult<Option<UsersModel>> {
+        let password = hash_password(&new_user.password)?;
+        let mut state = self.state();
+        if state.user_by_email(&new_user.email).is_some() {
+            return Ok(None);
+        }
+        let model = UsersModel {
+            id: nanoid!(),
+            name: new_user.name,
+            email: new_user.email.to_lowercase(),
+            avatar_url: new_user.avatar_url,
+            token_nonce: Some(0),
+            password: Some(password),
+            created_at: Some(Utc::now().into()),
+            timezone: DEFAULT_TIMEZONE.into(),
+            email_verified: false,
+            last_login_at: None,
+            name_customized: false,
+            status: UserStatus::Active as i16,
+            suspended_reason: None,
+            is_admin: false,
+        };
+        // invitations sent before signing up
+        for p in state.permissions.iter_mut().filter(|p| {
+            p.user_id.is_none() && p.user_email.as_deref() == Some(model.email.as_str())
+        }) {
+            p.user_id = Some(model.id.clone());
+            p.user_email = None;
+            p.updated_at = Some(Utc::now().into());
+        }
+        state.users.push(model.clone());
+        Ok(Some(model))
+    }
+
+    async fn get_user_by_id(&self, user_id: &str) -> StorageResult<Option<UsersModel>> {
+        Ok(self.state().users.iter().find(|u| u.id == user_id).cloned())
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> StorageResult<Option<UsersModel>> {
+        Ok(self.state().user_by_email(email).cloned())
+    }
+
+    async fn user_login(&self, login: UserLogin) -> StorageResult<Option<UsersModel>> {
+        let user = self.state().user_by_email(&login.email).cloned();
+        Ok(user.filter(|user| {
+            user.password
+                .as_deref()
+                .map(|hash| verify_password(&login.password, hash))
+                .unwrap_or(false)
+        }))
+    }
+
+    async fn create_normal_workspace(&self, user_id: String) -> StorageResult<Workspace> {
+        let mut state = self.state();
+        if !state.users.iter().any(|u| u.id == user_id) {
+            // the foreign key on permissions.user_id
+            return Err(StorageError::NotFound);
+        }
+        let now = Utc::now();
+        let workspace = Workspace {
+            id: nanoid!(),
+            public: false,
+            r#type: WorkspaceType::Normal,
+            created_at: now,
+            updated_at: Some(now),
+        };
+        state.workspaces.push(workspace.clone());
+        state.insert_permission(
+            workspace.id.clone(),
+            Some(user_id),
+            None,
+            PermissionType::Owner,
+            true,
+        )?;
+        Ok(workspace)
+    }
+
+    async fn get_workspace_by_id(
+        &self,
+        workspace_id: String,
+    ) -> StorageResult<Option<WorkspaceDetail>> {
+        let state = self.state();
+        let Some(workspace) = state.workspaces.iter().find(|w| w.id == workspace_id) else {
+            return Ok(None);
+        };
+        if workspace.r#type == WorkspaceType::Private {
+            return Ok(Some(WorkspaceDetail {
+                owner: None,
+                member_count: 0,
+                workspace: workspace.clone(),
+            }));
+        }
+
+        let members = state
+            .permissions
+            .iter()
+            .filter(|p| p.workspace_id == workspace_id);
+        let owner = members
+            .clone()
+            .find(|p| p.r#type == PermissionType::Owner as i16)
+            .and_then(|p| {
+                state
+                    .users
+                    .iter()
+                    .find(|u| Some(&u.id) == p.user_id.as_ref())
+            });
+        Ok(Some(WorkspaceDetail {
+            owner: owner.map(user),
+            member_count: members.filter(|p| p.accepted).count() as u64,
+            workspace: workspace.clone(),
+        }))
+    }
+
+    async fn get_user_workspaces(
+        &self,
+        user_id: String,
+    ) -> StorageResult<Vec<WorkspaceWithPermission>> {
+        let state = self.state();
+        let mut workspaces = state
+            .permissions
+            .iter()
+            .filter(|p| p.user_id.as_ref() == Some(&user_id) && p.accepted)
+            .filter_map(|p| {
+                state
+                    .workspaces
+                    .iter()
+                    .find(|w| w.id == p.workspace_id)
+                    .map(|w| (w, p.r#type))
+            })
+            .collect::<Vec<_>>();
+        workspaces.sort_by(|(a, _), (b, _)| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+
+        Ok(workspaces
+            .into_iter()
+            .map(|(w, permission)| WorkspaceWithPermission {
+                permission: permission.into(),
+                id: w.id.clone(),
+                public: w.public,
+                r#type: w.r#type,
+                updated_at: w.updated_at,
+            })
+            .collect())
+    }
+
+    async fn update_workspace(
+        &self,
+        workspace_id: String,
+        data: UpdateWorkspace,
+    ) -> StorageResult<Option<Workspace>> {
+        let mut state = self.state();
+        let Some(workspace) = state
+            .workspaces
+            .iter_mut()
+            .find(|w| w.id == workspace_id && w.r#type == WorkspaceType::Normal)
+        else {
+            return Ok(None);
+        };
+        workspace.public = data.public;
+        workspace.updated_at = Some(Utc::now());
+        Ok(Some(workspace.clone()))
+    }
+
+    async fn delete_workspace(&self, workspace_id: String) -> StorageResult<bool> {
+        let mut state = self.state();
+        if state.normal_workspace(&workspace_id).is_none() {
+            return Ok(false);
+        }
+        state.workspaces.retain(|w| w.id != workspace_id);
+        state.permissions.retain(|p| p.workspace_id != workspace_id);
+        Ok(true)
+    }
+
+    async fn can_read_workspace(
+        &self,
+        user_id: String,
+        workspace_id: String,
+    ) -> StorageResult<bool> {
+        let state = self.state();
+        Ok(state
+            .workspaces
+            .iter()
+            .find(|w| w.id == workspace_id)
+            .map(|w| {
+                w.public
+                    || state.permissions.iter().any(|p| {
+                        p.workspace_id == workspace_id
+                            && p.user_id.as_ref() == Some(&user_id)
+                            && p.accepted
+                    })
+            })
+            .unwrap_or(false))
+    }
+
+    async fn create_permission(
+        &self,
+        email: &str,
+        workspace_id: String,
+        permission_type: PermissionType,
+    ) -> StorageResult<CreatePermissionOutcome> {
+        if !validate_email(email) {
+            return Err(StorageError::InvalidEmail(email.to_string()));
+        }
+        let email = email.to_lowercase();
+        let mut state = self.state();
+        if state.normal_workspace(&workspace_id).is_none() {
+            return Ok(CreatePermissionOutcome::WorkspaceNotInvitable);
+        }
+        let registered = state.user_by_email(&email).cloned();
+        if state.permissions.iter().any(|p| {
+            p.workspace_id == workspace_id
+                && (p.user_email.as_ref() == Some(&email)
+                    || registered
+                        .as_ref()
+                        .is_some_and(|u| p.user_id.as_ref() == Some(&u.id)))
+        }) {
+            return Ok(CreatePermissionOutcome::AlreadyMember);
+        }
+        let id = state.insert_permission(
+            workspace_id,
+            registered.as_ref().map(|u| u.id.clone()),
+            registered.is_none().then(|| email.clone()),
+            permission_type,
+            false,
+        )?;
+
+        let cred = match registered {
+            Some(model) => UserCred::Registered(user(&model)),
+            None => UserCred::UnRegistered { email },
+        };
+        Ok(CreatePermissionOutcome::Created(id, cred))
+    }
+
+    async fn accept_permission(&self, permission_id: String) -> StorageResult<Option<Permission>> {
+        let mut state = self.state();
+        Ok(state
+            .permissions
+            .iter_mut()
+            .find(|p| p.id == permission_id)
+            .map(|p| {
+                p.accepted = true;
+                p.updated_at = Some(Utc::now().into());
+                permission(p)
+            }))
+    }
+
+    async fn get_permission(
+        &self,
+        user_id: String,
+        workspace_id: String,
+    ) -> StorageResult<Option<PermissionType>> {
+        Ok(self
+            .state()
+            .permissions
+            .iter()
+            .find(|p| p.user_id.as_ref() == Some(&user_id) && p.workspace_id == workspace_id)
+            .map(|p| p.r#type.into()))
+    }
+
+    async fn delete_permission(&self, permission_id: String) -> StorageResult<bool> {
+        let mut state = self.state();
+        let len = state.permissions.len();
+        state.permissions.retain(|p| p.id != permission_id);
+        Ok(state.permissions.len() != len)
+    }
+}
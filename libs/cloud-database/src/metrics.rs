@@ -0,0 +1,105 @@
+use super::CloudDatabase;
+use ::metrics::{counter, gauge, histogram};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Count a call in `jwst_storage_queries_total` by outcome and record its
+/// duration in `jwst_storage_query_duration_seconds`.
+pub(super) fn record(method: &'static str, ok: bool, elapsed: Duration) {
+    let outcome = if ok { "ok" } else { "error" };
+    counter!("jwst_storage_queries_total", 1, "method" => method, "outcome" => outcome);
+    histogram!(
+        "jwst_storage_query_duration_seconds",
+        elapsed.as_secs_f64(),
+        "method" => method
+    );
+}
+
+impl CloudDatabase {
+    /// Sample [`CloudDatabase::pool_stats`] into the `jwst_storage_pool_size`
+    /// and `jwst_storage_pool_idle` gauges every `interval`, until the
+    /// returned task is aborted.
+    pub fn spawn_pool_metrics(&self, interval: Duration) -> JoinHandle<()> {
+        let db = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let stats = db.pool_stats();
+                gauge!("jwst_storage_pool_size", stats.size as f64);
+                gauge!("jwst_storage_pool_idle", stats.idle as f64);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::CreateUser;
+    use metrics_util::{
+        debugging::{DebugValue, DebuggingRecorder, Snapshotter},
+        MetricKind,
+    };
+
+    #[tokio::test]
+    async fn metrics_recorded() -> anyhow::Result<()> {
+        // counts only what this thread records, the test runtime runs on it
+        let recorder = DebuggingRecorder::per_thread();
+        recorder.install()?;
+        let pool = CloudDatabase::init_pool("sqlite::memory:").await?;
+        // start test
+        let user = pool
+            .create_user(CreateUser {
+                avatar_url: None,
+                email: "xxx@xxx.xx".to_string(),
+                name: "xxx".to_string(),
+                password: "xxx".to_string(),
+            })
+            .await?
+            .unwrap();
+        pool.get_user_by_id(&user.id).await?;
+        pool.get_user_by_id(&user.id).await?;
+        let sampler = pool.spawn_pool_metrics(Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        sampler.abort();
+
+        let snapshot = Snapshotter::current_thread_snapshot().unwrap().into_vec();
+        let value = |kind, name: &str, labels: &[(&str, &str)]| {
+            snapshot
+                .iter()
+                .find(|(key, ..)| {
+                    key.kind() == kind
+                        && key.key().name() == name
+                        && labels.iter().all(|(label, value)| {
+                            key.key()
+                                .labels()
+                                .any(|l| l.key() == *label && l.value() == *value)
+                        })
+                })
+                .map(|(.., value)| value)
+        };
+        assert_eq!(
+            value(
+                MetricKind::Counter,
+                "jwst_storage_queries_total",
+                &[("method", "get_user_by_id"), ("outcome", "ok")]
+            ),
+            Some(&DebugValue::Counter(2))
+        );
+        assert!(matches!(
+            value(
+                MetricKind::Histogram,
+                "jwst_storage_query_duration_seconds",
+                &[("method", "create_user")]
+            ),
+            Some(DebugValue::Histogram(samples)) if samples.len() == 1
+        ));
+        assert!(matches!(
+            value(MetricKind::Gauge, "jwst_storage_pool_size", &[]),
+            Some(DebugValue::Gauge(size)) if size.into_inner() >= 1.0
+        ));
+
+        Ok(())
+    }
+}
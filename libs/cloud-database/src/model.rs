@@ -1,7 +1,8 @@
 // use super::*;
 // use sqlx::{postgres::PgRow, FromRow, Result, Row};
 
-use chrono::naive::serde::{ts_milliseconds, ts_seconds};
+use chrono::naive::serde::ts_seconds;
+use chrono::serde::{ts_milliseconds, ts_milliseconds_option};
 use chrono::{DateTime, Utc};
 use jwst_logger::error;
 use schemars::{JsonSchema, JsonSchema_repr};
@@ -10,6 +11,8 @@ use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use sqlx::{self, types::chrono::NaiveDateTime, FromRow, Type};
 
+use super::UsersModel;
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UserInfo {
     pub email: String,
@@ -33,6 +36,7 @@ pub struct FirebaseClaims {
     pub user_info: Option<UserInfo>,
 }
 
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct User {
     pub id: String,
@@ -41,12 +45,28 @@ pub struct User {
     pub avatar_url: Option<String>,
     #[serde(with = "ts_milliseconds")]
     #[schemars(with = "i64")]
-    pub created_at: NaiveDateTime,
+    #[cfg_attr(feature = "openapi", schema(value_type = i64))]
+    pub created_at: DateTime<Utc>,
+    /// IANA name, e.g. `America/New_York`
+    // tokens signed before users had a timezone don't carry it
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// see [`CloudDatabase::verify_email`](crate::CloudDatabase::verify_email)
+    #[serde(default)]
+    pub email_verified: bool,
+}
+
+/// Timezone of the users that didn't set one.
+pub const DEFAULT_TIMEZONE: &str = "UTC";
+
+fn default_timezone() -> String {
+    DEFAULT_TIMEZONE.into()
 }
 
 #[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UserWithNonce {
     pub user: User,
+    #[serde(skip_serializing)]
     pub token_nonce: i16,
 }
 
@@ -77,6 +97,7 @@ pub enum MakeToken {
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UserLogin {
     pub email: String,
+    #[serde(skip_serializing)]
     pub password: String,
 }
 
@@ -85,6 +106,7 @@ pub struct CreateUser {
     pub name: String,
     pub avatar_url: Option<String>,
     pub email: String,
+    #[serde(skip_serializing)]
     pub password: String,
 }
 
@@ -96,13 +118,14 @@ pub struct UserToken {
 
 #[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RefreshToken {
-    #[serde(with = "ts_milliseconds")]
+    #[serde(with = "chrono::naive::serde::ts_milliseconds")]
     #[schemars(with = "i64")]
     pub expires: NaiveDateTime,
     pub user_id: String,
     pub token_nonce: i16,
 }
 
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(
     Type, Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, JsonSchema_repr,
 )]
@@ -112,6 +135,14 @@ pub enum WorkspaceType {
     Normal = 1,
 }
 
+/// Ordering used by [`crate::CloudDatabase::get_user_workspaces_paged`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceSort {
+    #[default]
+    CreatedAtAsc,
+    CreatedAtDesc,
+}
+
 impl From<i16> for WorkspaceType {
     fn from(i: i16) -> Self {
         match i {
@@ -151,17 +182,28 @@ impl TryGetable for WorkspaceType {
     }
 }
 
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(FromQueryResult, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Workspace {
+    /// random nanoid assigned by `create_workspace`, it doesn't reveal how
+    /// many workspaces exist and is safe to hand out in public links
     pub id: String,
     pub public: bool,
     #[serde(rename = "type")]
     pub r#type: WorkspaceType,
     #[serde(with = "ts_milliseconds")]
     #[schemars(with = "i64")]
-    pub created_at: NaiveDateTime,
+    #[cfg_attr(feature = "openapi", schema(value_type = i64))]
+    pub created_at: DateTime<Utc>,
+    /// last change made through `update_workspace`, `None` for workspaces
+    /// created before it was tracked
+    #[serde(with = "ts_milliseconds_option")]
+    #[schemars(with = "Option<i64>")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<i64>))]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(FromQueryResult, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceWithPermission {
     pub permission: PermissionType,
@@ -174,8 +216,13 @@ pub struct WorkspaceWithPermission {
     // #[serde(with = "ts_milliseconds")]
     // #[schemars(with = "i64")]
     // pub created_at: NaiveDateTime,
+    #[serde(with = "ts_milliseconds_option")]
+    #[schemars(with = "Option<i64>")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<i64>))]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceDetail {
     // None if it's private
@@ -210,6 +257,32 @@ pub struct UpdateWorkspace {
     pub public: bool,
 }
 
+/// A change to a workspace setting, see
+/// [`CloudDatabase::get_workspace_audit`](crate::CloudDatabase::get_workspace_audit).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceAuditEntry {
+    pub id: String,
+    pub workspace_id: String,
+    /// None when the change wasn't made by a user or the user was deleted
+    pub actor_user_id: Option<String>,
+    /// the setting that changed, `public` for the visibility
+    pub action: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    #[serde(with = "ts_milliseconds")]
+    #[schemars(with = "i64")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Profile fields to change, see `CloudDatabase::update_user`. Fields left
+/// `None` keep their value.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateUser {
+    pub name: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(
     Type,
     Serialize_repr,
@@ -271,6 +344,7 @@ impl TryGetable for PermissionType {
     }
 }
 
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Permission {
     pub id: String,
@@ -283,7 +357,14 @@ pub struct Permission {
     pub accepted: bool,
     #[serde(with = "ts_milliseconds")]
     #[schemars(with = "i64")]
-    pub created_at: NaiveDateTime,
+    #[cfg_attr(feature = "openapi", schema(value_type = i64))]
+    pub created_at: DateTime<Utc>,
+    /// last change to the role or its acceptance, `None` for permissions
+    /// created before it was tracked
+    #[serde(with = "ts_milliseconds_option")]
+    #[schemars(with = "Option<i64>")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<i64>))]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 impl PermissionType {
@@ -305,6 +386,33 @@ pub struct CreatePermission {
     pub email: String,
 }
 
+/// Whether `email` looks like an address mail can be sent to: a single `@`
+/// between a local part and a domain of at least two dot separated labels.
+/// Meant to reject obvious garbage, not to implement RFC 5322.
+pub fn validate_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    let label = |label: &str| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+    };
+    email.len() <= 254
+        && !local.is_empty()
+        && local.len() <= 64
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..")
+        && !local
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || "@<>()[],;:\\\"".contains(c))
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(label)
+}
+
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum UserCred {
@@ -312,6 +420,158 @@ pub enum UserCred {
     UnRegistered { email: String },
 }
 
+/// Result of [`CloudDatabase::create_permission`](crate::CloudDatabase::create_permission).
+#[derive(Debug, Clone)]
+pub enum CreatePermissionOutcome {
+    /// id of the new permission and the invited user
+    Created(String, UserCred),
+    /// the email already has a permission in the workspace, accepted or not
+    AlreadyMember,
+    /// the workspace doesn't exist or is private
+    WorkspaceNotInvitable,
+}
+
+impl CreatePermissionOutcome {
+    pub fn created(self) -> Option<(String, UserCred)> {
+        match self {
+            Self::Created(id, user) => Some((id, user)),
+            _ => None,
+        }
+    }
+}
+
+/// Result of [`CloudDatabase::upsert_user_from_google`](crate::CloudDatabase::upsert_user_from_google).
+#[derive(Debug, Clone)]
+pub enum UpsertUserOutcome {
+    /// a new user linked to the Google account
+    Created(UsersModel),
+    /// the user already linked, with the profile from Google applied
+    Updated(UsersModel),
+}
+
+impl UpsertUserOutcome {
+    pub fn user(self) -> UsersModel {
+        match self {
+            Self::Created(user) | Self::Updated(user) => user,
+        }
+    }
+}
+
+/// A password reset to mail, see
+/// [`CloudDatabase::create_password_reset`](crate::CloudDatabase::create_password_reset).
+#[derive(Debug, Clone)]
+pub struct ResetToken {
+    pub user_id: String,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// How a user signed in, stored with every [`LoginEvent`].
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[repr(i16)]
+pub enum LoginMethod {
+    Password = 0,
+    Google = 1,
+    Refresh = 2,
+}
+
+impl From<i16> for LoginMethod {
+    fn from(i: i16) -> Self {
+        match i {
+            0 => LoginMethod::Password,
+            1 => LoginMethod::Google,
+            2 => LoginMethod::Refresh,
+            _ => {
+                error!("invalid login method: {}", i);
+                LoginMethod::Password
+            }
+        }
+    }
+}
+
+/// Whether an account may be used, see
+/// [`CloudDatabase::suspend_user`](crate::CloudDatabase::suspend_user).
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[repr(i16)]
+pub enum UserStatus {
+    #[default]
+    Active = 0,
+    Suspended = 1,
+}
+
+impl From<i16> for UserStatus {
+    fn from(i: i16) -> Self {
+        match i {
+            0 => UserStatus::Active,
+            1 => UserStatus::Suspended,
+            _ => {
+                error!("invalid user status: {}", i);
+                // unknown states don't grant access
+                UserStatus::Suspended
+            }
+        }
+    }
+}
+
+/// Where a login came from, as seen by the HTTP layer.
+#[derive(Debug, Default, Clone)]
+pub struct LoginClient {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// A successful login, see
+/// [`CloudDatabase::get_login_events`](crate::CloudDatabase::get_login_events).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LoginEvent {
+    pub id: String,
+    pub user_id: String,
+    pub method: LoginMethod,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    #[serde(with = "ts_milliseconds")]
+    #[schemars(with = "i64")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// What [`CloudDatabase::delete_user`](crate::CloudDatabase::delete_user)
+/// removed along with the user.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeletionReport {
+    /// the private workspace and the normal ones the user was alone in
+    pub deleted_workspaces: Vec<String>,
+    /// memberships and invitations in other users' workspaces
+    pub removed_permissions: u64,
+}
+
+/// Which users [`CloudDatabase::list_users`](crate::CloudDatabase::list_users)
+/// lists.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListUsers {
+    pub limit: u64,
+    /// `next_cursor` of the previous page, None for the first one
+    pub cursor: Option<String>,
+    /// start of the name or email, ignoring case
+    pub search: Option<String>,
+    /// count every matching user as well, a scan on big tables
+    #[serde(default)]
+    pub total_count: bool,
+}
+
+/// A page of a listing, the next one starts at `next_cursor`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// None on the last page
+    pub next_cursor: Option<String>,
+    /// only when requested
+    pub total_count: Option<u64>,
+}
+
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Member {
     pub id: String,
@@ -321,7 +581,12 @@ pub struct Member {
     pub r#type: PermissionType,
     #[serde(with = "ts_milliseconds")]
     #[schemars(with = "i64")]
-    pub created_at: NaiveDateTime,
+    #[cfg_attr(feature = "openapi", schema(value_type = i64))]
+    pub created_at: DateTime<Utc>,
+    /// of the registered user, e.g. to grey out suspended ones; invitations
+    /// by email are active
+    #[serde(default)]
+    pub status: UserStatus,
 }
 
 #[derive(FromQueryResult)]
@@ -336,6 +601,9 @@ pub struct MemberResult {
     // .column_as(UsersColumn::Email, "user_table_email")
     // .column_as(UsersColumn::AvatarUrl, "user_avatar_url")
     // .column_as(UsersColumn::CreatedAt, "user_created_at")
+    // .column_as(UsersColumn::Timezone, "user_timezone")
+    // .column_as(UsersColumn::EmailVerified, "user_email_verified")
+    // .column_as(UsersColumn::Status, "user_status")
     pub id: String,
     pub r#type: PermissionType,
     pub user_email: Option<String>,
@@ -346,6 +614,9 @@ pub struct MemberResult {
     pub user_table_email: Option<String>,
     pub user_avatar_url: Option<String>,
     pub user_created_at: Option<DateTime<Utc>>,
+    pub user_timezone: Option<String>,
+    pub user_email_verified: Option<bool>,
+    pub user_status: Option<i16>,
 }
 
 impl From<&MemberResult> for Member {
@@ -356,7 +627,9 @@ impl From<&MemberResult> for Member {
                 name: r.user_name.clone().unwrap(),
                 email: r.user_table_email.clone().unwrap(),
                 avatar_url: r.user_avatar_url.clone(),
-                created_at: r.user_created_at.unwrap_or_default().naive_local(),
+                created_at: r.user_created_at.unwrap_or_default(),
+                timezone: r.user_timezone.clone().unwrap_or_else(default_timezone),
+                email_verified: r.user_email_verified.unwrap_or_default(),
             })
         } else {
             UserCred::UnRegistered {
@@ -368,11 +641,13 @@ impl From<&MemberResult> for Member {
             user,
             accepted: r.accepted,
             r#type: r.r#type.clone(),
-            created_at: r.created_at.unwrap_or_default().naive_local(),
+            created_at: r.created_at.unwrap_or_default(),
+            status: r.user_status.map(Into::into).unwrap_or_default(),
         }
     }
 }
 
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UserInWorkspace {
     #[serde(flatten)]
@@ -399,3 +674,194 @@ pub struct BigId {
 pub struct Count {
     pub count: i64,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn ts(millis: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(millis).unwrap()
+    }
+
+    fn user() -> User {
+        User {
+            id: "user".into(),
+            name: "xxx".into(),
+            email: "xxx@xxx.xx".into(),
+            avatar_url: None,
+            created_at: ts(1677122059817),
+            timezone: "UTC".into(),
+            email_verified: false,
+        }
+    }
+
+    fn workspace() -> Workspace {
+        Workspace {
+            id: "workspace".into(),
+            public: true,
+            r#type: WorkspaceType::Normal,
+            created_at: ts(1677122059817),
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn model_wire_format() {
+        assert_eq!(
+            serde_json::to_value(user()).unwrap(),
+            json!({
+                "id": "user",
+                "name": "xxx",
+                "email": "xxx@xxx.xx",
+                "avatar_url": null,
+                "created_at": 1677122059817i64,
+                "timezone": "UTC",
+                "email_verified": false,
+            })
+        );
+        // claims of tokens signed before users had a timezone
+        let legacy: User = serde_json::from_value(json!({
+            "id": "user",
+            "name": "xxx",
+            "email": "xxx@xxx.xx",
+            "avatar_url": null,
+            "created_at": 1677122059817i64,
+        }))
+        .unwrap();
+        assert_eq!(legacy.timezone, DEFAULT_TIMEZONE);
+        assert!(!legacy.email_verified);
+        assert_eq!(
+            serde_json::to_value(WorkspaceDetail {
+                owner: Some(user()),
+                member_count: 2,
+                workspace: workspace(),
+            })
+            .unwrap(),
+            json!({
+                "owner": serde_json::to_value(user()).unwrap(),
+                "member_count": 2,
+                "id": "workspace",
+                "public": true,
+                "type": 1,
+                "created_at": 1677122059817i64,
+                "updated_at": null,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(Permission {
+                id: "permission".into(),
+                r#type: PermissionType::Admin,
+                workspace_id: "workspace".into(),
+                user_id: None,
+                user_email: Some("yyy@yyy.yy".into()),
+                accepted: false,
+                created_at: ts(1677122059817),
+                updated_at: Some(ts(1677122060000)),
+            })
+            .unwrap(),
+            json!({
+                "id": "permission",
+                "type": 10,
+                "workspace_id": "workspace",
+                "user_id": null,
+                "user_email": "yyy@yyy.yy",
+                "accepted": false,
+                "created_at": 1677122059817i64,
+                "updated_at": 1677122060000i64,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(Member {
+                id: "permission".into(),
+                user: UserCred::Registered(user()),
+                accepted: true,
+                r#type: PermissionType::Write,
+                created_at: ts(1677122059817),
+                status: UserStatus::Active,
+            })
+            .unwrap(),
+            json!({
+                "id": "permission",
+                "user": {
+                    "type": "Registered",
+                    "id": "user",
+                    "name": "xxx",
+                    "email": "xxx@xxx.xx",
+                    "avatar_url": null,
+                    "created_at": 1677122059817i64,
+                    "timezone": "UTC",
+                    "email_verified": false,
+                },
+                "accepted": true,
+                "type": 1,
+                "created_at": 1677122059817i64,
+                "status": "active",
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(UserInWorkspace {
+                user: UserCred::UnRegistered {
+                    email: "yyy@yyy.yy".into(),
+                },
+                in_workspace: false,
+            })
+            .unwrap(),
+            json!({
+                "type": "UnRegistered",
+                "email": "yyy@yyy.yy",
+                "in_workspace": false,
+            })
+        );
+    }
+
+    #[test]
+    fn model_validate_email() {
+        for email in [
+            "a@b.co",
+            "first.last+tag@sub.example.com",
+            "x@xn--bcher-kva.ch",
+        ] {
+            assert!(validate_email(email), "{email}");
+        }
+        for email in [
+            "not-an-email",
+            "",
+            "@b.co",
+            "a@",
+            "a@b",
+            "a@@b.co",
+            "a@b@c.co",
+            "a b@c.co",
+            "a@b..co",
+            "a@-b.co",
+            ".a@b.co",
+            "a..b@c.co",
+        ] {
+            assert!(!validate_email(email), "{email}");
+        }
+    }
+
+    #[test]
+    fn model_skip_secrets() {
+        let login = serde_json::to_value(UserLogin {
+            email: "xxx@xxx.xx".into(),
+            password: "secret".into(),
+        })
+        .unwrap();
+        assert_eq!(login, json!({ "email": "xxx@xxx.xx" }));
+
+        let user = serde_json::to_value(UserWithNonce {
+            user: user(),
+            token_nonce: 3,
+        })
+        .unwrap();
+        assert!(user.get("token_nonce").is_none());
+
+        // still accepted as input
+        let login: UserLogin =
+            serde_json::from_value(json!({ "email": "xxx@xxx.xx", "password": "secret" })).unwrap();
+        assert_eq!(login.password, "secret");
+    }
+}
@@ -0,0 +1,88 @@
+use super::types::StorageError;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, DbErr, RuntimeErr};
+use std::time::{Duration, Instant};
+
+/// Snapshot of the connection pool.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    /// connections currently opened by the pool, idle or in use
+    pub size: u32,
+    /// opened connections that are not checked out
+    pub idle: u32,
+}
+
+impl PoolStats {
+    pub fn in_use(&self) -> u32 {
+        self.size.saturating_sub(self.idle)
+    }
+}
+
+fn stats<DB: sqlx::Database>(pool: &sqlx::Pool<DB>) -> PoolStats {
+    PoolStats {
+        size: pool.size(),
+        idle: pool.num_idle() as u32,
+    }
+}
+
+pub(super) fn pool_stats(conn: &DatabaseConnection) -> PoolStats {
+    match conn.get_database_backend() {
+        #[cfg(feature = "mysql")]
+        DbBackend::MySql => stats(conn.get_mysql_connection_pool()),
+        #[cfg(feature = "postgres")]
+        DbBackend::Postgres => stats(conn.get_postgres_connection_pool()),
+        #[cfg(feature = "sqlite")]
+        DbBackend::Sqlite => stats(conn.get_sqlite_connection_pool()),
+        #[allow(unreachable_patterns)]
+        _ => PoolStats::default(),
+    }
+}
+
+pub(super) fn is_closed(conn: &DatabaseConnection) -> bool {
+    match conn.get_database_backend() {
+        #[cfg(feature = "mysql")]
+        DbBackend::MySql => conn.get_mysql_connection_pool().is_closed(),
+        #[cfg(feature = "postgres")]
+        DbBackend::Postgres => conn.get_postgres_connection_pool().is_closed(),
+        #[cfg(feature = "sqlite")]
+        DbBackend::Sqlite => conn.get_sqlite_connection_pool().is_closed(),
+        #[allow(unreachable_patterns)]
+        _ => false,
+    }
+}
+
+async fn select_one<DB>(pool: &sqlx::Pool<DB>) -> Result<(), sqlx::Error>
+where
+    DB: sqlx::Database,
+    for<'c> &'c mut DB::Connection: sqlx::Executor<'c, Database = DB>,
+{
+    // acquire from sqlx directly, sea-orm reports every acquire failure
+    // as `DbErr::ConnectionAcquire`
+    let mut conn = pool.acquire().await?;
+    sqlx::Executor::execute(&mut *conn, "SELECT 1").await?;
+    Ok(())
+}
+
+fn ping_error(err: sqlx::Error) -> StorageError {
+    match err {
+        sqlx::Error::PoolTimedOut => StorageError::PoolExhausted,
+        sqlx::Error::PoolClosed => StorageError::PoolClosed,
+        sqlx::Error::Io(_) | sqlx::Error::Tls(_) => StorageError::Unavailable(err),
+        err => StorageError::Database(DbErr::Conn(RuntimeErr::SqlxError(err))),
+    }
+}
+
+pub(super) async fn ping(conn: &DatabaseConnection) -> Result<Duration, StorageError> {
+    let start = Instant::now();
+    let result = match conn.get_database_backend() {
+        #[cfg(feature = "mysql")]
+        DbBackend::MySql => select_one(conn.get_mysql_connection_pool()).await,
+        #[cfg(feature = "postgres")]
+        DbBackend::Postgres => select_one(conn.get_postgres_connection_pool()).await,
+        #[cfg(feature = "sqlite")]
+        DbBackend::Sqlite => select_one(conn.get_sqlite_connection_pool()).await,
+        #[allow(unreachable_patterns)]
+        _ => return Err(StorageError::Database(DbErr::ConnectionAcquire)),
+    };
+    result.map_err(ping_error)?;
+    Ok(start.elapsed())
+}
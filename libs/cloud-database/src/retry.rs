@@ -0,0 +1,238 @@
+use super::types::StorageError;
+use jwst_logger::warn;
+use rand::Rng;
+use sea_orm::{DbErr, RuntimeErr};
+use sqlx::error::DatabaseError;
+use std::{fmt::Display, future::Future, time::Duration};
+
+/// How mutations are retried after transient failures such as lock
+/// contention, serialization failures or deadlocks. Reads follow the same
+/// policy and are also retried after connection errors, see [`retry_read`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// retries after the first attempt, 0 disables retrying
+    pub max_retries: u32,
+    /// delay before the first retry, doubled on every following one
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(20),
+            max_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << retry.min(16))
+            .min(self.max_delay);
+        // jitter so concurrent writers don't retry in lockstep
+        backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+pub(crate) trait Transient {
+    fn is_transient(&self) -> bool;
+
+    /// The connection failed or none could be taken from the pool in time.
+    /// Only safe to retry when the failed statement changes nothing, a
+    /// write may have been applied before its connection dropped.
+    fn is_connection_error(&self) -> bool;
+}
+
+impl Transient for DbErr {
+    fn is_transient(&self) -> bool {
+        match self {
+            DbErr::Conn(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+            | DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+            | DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(e))) => {
+                is_transient_error(e.as_ref())
+            }
+            _ => false,
+        }
+    }
+
+    fn is_connection_error(&self) -> bool {
+        match self {
+            DbErr::ConnectionAcquire => true,
+            DbErr::Conn(RuntimeErr::SqlxError(e))
+            | DbErr::Exec(RuntimeErr::SqlxError(e))
+            | DbErr::Query(RuntimeErr::SqlxError(e)) => {
+                matches!(e, sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Transient for StorageError {
+    fn is_transient(&self) -> bool {
+        matches!(self, StorageError::Database(e) if e.is_transient())
+    }
+
+    fn is_connection_error(&self) -> bool {
+        matches!(self, StorageError::Database(e) if e.is_connection_error())
+    }
+}
+
+fn is_transient_error(err: &dyn DatabaseError) -> bool {
+    match err.code().as_deref() {
+        // sqlite: SQLITE_BUSY, SQLITE_LOCKED, SQLITE_BUSY_RECOVERY,
+        // SQLITE_LOCKED_SHAREDCACHE, SQLITE_BUSY_SNAPSHOT
+        Some("5") | Some("6") | Some("261") | Some("262") | Some("517") => true,
+        // postgres & mysql: serialization_failure / deadlock, postgres: deadlock_detected
+        Some("40001") | Some("40P01") => true,
+        #[cfg(feature = "mysql")]
+        _ => err
+            .try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>()
+            // mysql: ER_LOCK_WAIT_TIMEOUT
+            .map(|e| e.number() == 1205)
+            .unwrap_or(false),
+        #[cfg(not(feature = "mysql"))]
+        _ => false,
+    }
+}
+
+/// Run `op` until it succeeds, fails with a non-transient error or the
+/// policy runs out of retries.
+pub(crate) async fn retry<T, E, F, Fut>(policy: RetryPolicy, op: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Transient + Display,
+{
+    retry_if(policy, op, E::is_transient).await
+}
+
+/// [`retry`] for read-only queries, which are also retried after connection
+/// errors such as a dropped socket or an exhausted pool.
+pub(crate) async fn retry_read<T, E, F, Fut>(policy: RetryPolicy, op: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Transient + Display,
+{
+    retry_if(policy, op, |e| e.is_transient() || e.is_connection_error()).await
+}
+
+async fn retry_if<T, E, F, Fut>(
+    policy: RetryPolicy,
+    mut op: F,
+    retryable: impl Fn(&E) -> bool,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    let mut retries = 0;
+    loop {
+        match op().await {
+            Err(e) if retries < policy.max_retries && retryable(&e) => {
+                let delay = policy.delay(retries);
+                warn!("transient database error, retry in {:?}: {}", delay, e);
+                tokio::time::sleep(delay).await;
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn retry_policy_delay() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+        for retry in 0..10 {
+            let expected = (100 << retry).min(500);
+            let delay = policy.delay(retry).as_millis() as u64;
+            assert!(
+                delay >= expected / 2 && delay <= expected,
+                "{retry}: {delay}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_non_transient() {
+        let mut attempts = 0;
+        let result: Result<(), DbErr> = retry(RetryPolicy::default(), || {
+            attempts += 1;
+            async { Err(DbErr::RecordNotFound("xxx".into())) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    fn io_error() -> DbErr {
+        DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Io(
+            std::io::ErrorKind::ConnectionReset.into(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn retry_read_connection_error() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            ..Default::default()
+        };
+        for err in [io_error, || DbErr::ConnectionAcquire] {
+            // fails once, then succeeds
+            let mut attempts = 0;
+            let result = retry_read(policy, || {
+                attempts += 1;
+                let result = if attempts == 1 {
+                    Err(err())
+                } else {
+                    Ok(attempts)
+                };
+                async move { result }
+            })
+            .await;
+            assert_eq!(result.unwrap(), 2);
+        }
+
+        let mut attempts = 0;
+        let result: Result<(), DbErr> = retry_read(policy, || {
+            attempts += 1;
+            async { Err(io_error()) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, policy.max_retries + 1);
+    }
+
+    #[tokio::test]
+    async fn retry_write_connection_error() {
+        let mut attempts = 0;
+        let result: Result<(), DbErr> = retry(RetryPolicy::default(), || {
+            attempts += 1;
+            async { Err(io_error()) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
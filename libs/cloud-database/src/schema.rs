@@ -0,0 +1,109 @@
+use super::entities::prelude::*;
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, Iden, Iterable, Statement,
+};
+use std::fmt;
+
+/// Differences between the tables the entities expect and the live database.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SchemaReport {
+    pub missing_tables: Vec<String>,
+    /// `(table, column)` pairs the entities use but the database lacks
+    pub missing_columns: Vec<(String, String)>,
+    /// `(table, column)` pairs present in the database but unknown to the entities
+    pub extra_columns: Vec<(String, String)>,
+}
+
+impl SchemaReport {
+    pub fn is_empty(&self) -> bool {
+        self.missing_tables.is_empty()
+            && self.missing_columns.is_empty()
+            && self.extra_columns.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut issues = vec![];
+        for table in &self.missing_tables {
+            issues.push(format!("missing table {table}"));
+        }
+        for (table, column) in &self.missing_columns {
+            issues.push(format!("missing column {table}.{column}"));
+        }
+        for (table, column) in &self.extra_columns {
+            issues.push(format!("extra column {table}.{column}"));
+        }
+        write!(f, "{}", issues.join(", "))
+    }
+}
+
+fn expected_columns<E: EntityTrait>(entity: E) -> (String, Vec<String>) {
+    (
+        entity.table_name().to_string(),
+        E::Column::iter().map(|c| c.to_string()).collect(),
+    )
+}
+
+async fn table_columns(db: &DatabaseConnection, table: &str) -> Result<Vec<String>, DbErr> {
+    let backend = db.get_database_backend();
+    let stmt = match backend {
+        DbBackend::Sqlite => Statement::from_sql_and_values(
+            backend,
+            "SELECT name AS column_name FROM pragma_table_info(?)",
+            [table.into()],
+        ),
+        DbBackend::Postgres => Statement::from_sql_and_values(
+            backend,
+            "SELECT column_name::text AS column_name FROM information_schema.columns \
+             WHERE table_schema = current_schema() AND table_name = $1",
+            [table.into()],
+        ),
+        DbBackend::MySql => Statement::from_sql_and_values(
+            backend,
+            "SELECT column_name AS column_name FROM information_schema.columns \
+             WHERE table_schema = DATABASE() AND table_name = ?",
+            [table.into()],
+        ),
+    };
+
+    db.query_all(stmt)
+        .await?
+        .iter()
+        .map(|row| row.try_get::<String>("", "column_name"))
+        .collect()
+}
+
+pub(super) async fn verify_schema(db: &DatabaseConnection) -> Result<SchemaReport, DbErr> {
+    let mut report = SchemaReport::default();
+
+    for (table, expected) in [
+        expected_columns(Users),
+        expected_columns(GoogleUsers),
+        expected_columns(Workspaces),
+        expected_columns(Permissions),
+        expected_columns(EmailChanges),
+        expected_columns(VerificationTokens),
+        expected_columns(PasswordResets),
+        expected_columns(LoginEvents),
+        expected_columns(LoginAttempts),
+        expected_columns(WorkspaceAudit),
+        expected_columns(UserTotp),
+        expected_columns(RecoveryCodes),
+    ] {
+        let actual = table_columns(db, &table).await?;
+        if actual.is_empty() {
+            report.missing_tables.push(table);
+            continue;
+        }
+
+        for column in expected.iter().filter(|c| !actual.contains(c)) {
+            report.missing_columns.push((table.clone(), column.clone()));
+        }
+        for column in actual.iter().filter(|c| !expected.contains(c)) {
+            report.extra_columns.push((table.clone(), column.clone()));
+        }
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,61 @@
+use jwst_logger::warn;
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Calls running longer than this are logged, shared by all clones of a
+/// `CloudDatabase` so it can be changed at runtime.
+#[derive(Debug, Clone)]
+pub(super) struct SlowQueryThreshold(Arc<AtomicU64>);
+
+impl Default for SlowQueryThreshold {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(250))
+    }
+}
+
+impl SlowQueryThreshold {
+    fn new(threshold: Duration) -> Self {
+        Self(Arc::new(AtomicU64::new(Self::micros(threshold))))
+    }
+
+    fn micros(threshold: Duration) -> u64 {
+        threshold.as_micros().try_into().unwrap_or(u64::MAX)
+    }
+
+    pub(super) fn get(&self) -> Duration {
+        Duration::from_micros(self.0.load(Ordering::Relaxed))
+    }
+
+    pub(super) fn set(&self, threshold: Duration) {
+        self.0.store(Self::micros(threshold), Ordering::Relaxed);
+    }
+}
+
+/// Time `call`, warn when it took longer than `slow` and feed the metrics
+/// when they are enabled. The warning is emitted in the span of the method,
+/// which holds its identifiers.
+pub(super) async fn observe<T, E>(
+    method: &'static str,
+    slow: Option<Duration>,
+    call: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let start = Instant::now();
+    let result = call.await;
+    let elapsed = start.elapsed();
+    if slow.is_some_and(|slow| elapsed > slow) {
+        warn!(
+            method,
+            elapsed_ms = elapsed.as_millis() as u64,
+            "slow database call"
+        );
+    }
+    #[cfg(feature = "metrics")]
+    super::metrics::record(method, result.is_ok(), elapsed);
+    result
+}
@@ -0,0 +1,318 @@
+use super::{
+    model::{
+        CreatePermissionOutcome, CreateUser, Permission, PermissionType, UpdateWorkspace,
+        UserLogin, Workspace, WorkspaceDetail, WorkspaceWithPermission,
+    },
+    types::StorageResult,
+    CloudDatabase, UsersModel,
+};
+use async_trait::async_trait;
+
+/// The storage operations used by the sync and HTTP layers, implemented by
+/// [`CloudDatabase`] and, with the `testing` feature, by an in-memory
+/// `MemoryStorage` for unit tests that shouldn't need a database.
+#[async_trait]
+pub trait DbStorage: Send + Sync {
+    async fn create_user(&self, user: CreateUser) -> StorageResult<Option<UsersModel>>;
+
+    async fn get_user_by_id(&self, user_id: &str) -> StorageResult<Option<UsersModel>>;
+
+    async fn get_user_by_email(&self, email: &str) -> StorageResult<Option<UsersModel>>;
+
+    async fn user_login(&self, login: UserLogin) -> StorageResult<Option<UsersModel>>;
+
+    async fn create_normal_workspace(&self, user_id: String) -> StorageResult<Workspace>;
+
+    async fn get_workspace_by_id(
+        &self,
+        workspace_id: String,
+    ) -> StorageResult<Option<WorkspaceDetail>>;
+
+    async fn get_user_workspaces(
+        &self,
+        user_id: String,
+    ) -> StorageResult<Vec<WorkspaceWithPermission>>;
+
+    async fn update_workspace(
+        &self,
+        workspace_id: String,
+        data: UpdateWorkspace,
+    ) -> StorageResult<Option<Workspace>>;
+
+    async fn delete_workspace(&self, workspace_id: String) -> StorageResult<bool>;
+
+    async fn can_read_workspace(
+        &self,
+        user_id: String,
+        workspace_id: String,
+    ) -> StorageResult<bool>;
+
+    async fn create_permission(
+        &self,
+        email: &str,
+        workspace_id: String,
+        permission_type: PermissionType,
+    ) -> StorageResult<CreatePermissionOutcome>;
+
+    async fn accept_permission(&self, permission_id: String) -> StorageResult<Option<Permission>>;
+
+    async fn get_permission(
+        &self,
+        user_id: String,
+        workspace_id: String,
+    ) -> StorageResult<Option<PermissionType>>;
+
+    async fn delete_permission(&self, permission_id: String) -> StorageResult<bool>;
+}
+
+#[async_trait]
+impl DbStorage for CloudDatabase {
+    async fn create_user(&self, user: CreateUser) -> StorageResult<Option<UsersModel>> {
+        CloudDatabase::create_user(self, user).await
+    }
+
+    async fn get_user_by_id(&self, user_id: &str) -> StorageResult<Option<UsersModel>> {
+        CloudDatabase::get_user_by_id(self, user_id).await
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> StorageResult<Option<UsersModel>> {
+        CloudDatabase::get_user_by_email(self, email).await
+    }
+
+    async fn user_login(&self, login: UserLogin) -> StorageResult<Option<UsersModel>> {
+        CloudDatabase::user_login(self, login).await
+    }
+
+    async fn create_normal_workspace(&self, user_id: String) -> StorageResult<Workspace> {
+        CloudDatabase::create_normal_workspace(self, user_id).await
+    }
+
+    async fn get_workspace_by_id(
+        &self,
+        workspace_id: String,
+    ) -> StorageResult<Option<WorkspaceDetail>> {
+        CloudDatabase::get_workspace_by_id(self, workspace_id).await
+    }
+
+    async fn get_user_workspaces(
+        &self,
+        user_id: String,
+    ) -> StorageResult<Vec<WorkspaceWithPermission>> {
+        CloudDatabase::get_user_workspaces(self, user_id).await
+    }
+
+    async fn update_workspace(
+        &self,
+        workspace_id: String,
+        data: UpdateWorkspace,
+    ) -> StorageResult<Option<Workspace>> {
+        CloudDatabase::update_workspace(self, workspace_id, data).await
+    }
+
+    async fn delete_workspace(&self, workspace_id: String) -> StorageResult<bool> {
+        CloudDatabase::delete_workspace(self, workspace_id).await
+    }
+
+    async fn can_read_workspace(
+        &self,
+        user_id: String,
+        workspace_id: String,
+    ) -> StorageResult<bool> {
+        CloudDatabase::can_read_workspace(self, user_id, workspace_id).await
+    }
+
+    async fn create_permission(
+        &self,
+        email: &str,
+        workspace_id: String,
+        permission_type: PermissionType,
+    ) -> StorageResult<CreatePermissionOutcome> {
+        CloudDatabase::create_permission(self, email, workspace_id, permission_type).await
+    }
+
+    async fn accept_permission(&self, permission_id: String) -> StorageResult<Option<Permission>> {
+        CloudDatabase::accept_permission(self, permission_id).await
+    }
+
+    async fn get_permission(
+        &self,
+        user_id: String,
+        workspace_id: String,
+    ) -> StorageResult<Option<PermissionType>> {
+        CloudDatabase::get_permission(self, user_id, workspace_id).await
+    }
+
+    async fn delete_permission(&self, permission_id: String) -> StorageResult<bool> {
+        CloudDatabase::delete_permission(self, permission_id).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::fixtures;
+    use crate::{model::UserCred, types::StorageError};
+
+    /// Behaviour every implementation must share.
+    async fn storage_behaviour(storage: &dyn DbStorage) -> anyhow::Result<()> {
+        let owner = storage
+            .create_user(fixtures::user("owner@xxx.xx"))
+            .await?
+            .unwrap();
+        // emails are unique
+        assert!(storage
+            .create_user(fixtures::user("owner@xxx.xx"))
+            .await?
+            .is_none());
+        assert_eq!(
+            storage.get_user_by_id(&owner.id).await?.unwrap().email,
+            "owner@xxx.xx"
+        );
+        // emails are case-insensitive
+        assert_eq!(
+            storage.get_user_by_email("Owner@XXX.xx").await?.unwrap().id,
+            owner.id
+        );
+        assert!(storage
+            .create_user(fixtures::user("OWNER@xxx.xx"))
+            .await?
+            .is_none());
+        assert!(storage
+            .user_login(UserLogin {
+                email: "owner@xxx.xx".into(),
+                password: fixtures::PASSWORD.into(),
+            })
+            .await?
+            .is_some());
+        assert!(storage
+            .user_login(UserLogin {
+                email: "owner@xxx.xx".into(),
+                password: "yyy".into(),
+            })
+            .await?
+            .is_none());
+
+        let workspace = storage.create_normal_workspace(owner.id.clone()).await?;
+        let detail = storage
+            .get_workspace_by_id(workspace.id.clone())
+            .await?
+            .unwrap();
+        assert_eq!(detail.owner.unwrap().id, owner.id);
+        assert_eq!(detail.member_count, 1);
+
+        // invited before signing up
+        let (permission_id, user) = storage
+            .create_permission("member@xxx.xx", workspace.id.clone(), PermissionType::Read)
+            .await?
+            .created()
+            .unwrap();
+        assert!(matches!(user, UserCred::UnRegistered { .. }));
+        let member = storage
+            .create_user(fixtures::user("member@xxx.xx"))
+            .await?
+            .unwrap();
+        assert!(
+            !storage
+                .can_read_workspace(member.id.clone(), workspace.id.clone())
+                .await?
+        );
+        let permission = storage
+            .accept_permission(permission_id.clone())
+            .await?
+            .unwrap();
+        assert!(permission.accepted);
+        assert_eq!(permission.user_id, Some(member.id.clone()));
+        assert!(
+            storage
+                .can_read_workspace(member.id.clone(), workspace.id.clone())
+                .await?
+        );
+        assert_eq!(
+            storage
+                .get_permission(member.id.clone(), workspace.id.clone())
+                .await?,
+            Some(PermissionType::Read)
+        );
+        assert_eq!(
+            storage.get_user_workspaces(member.id.clone()).await?.len(),
+            1
+        );
+
+        assert!(matches!(
+            storage
+                .create_permission("MEMBER@xxx.xx", workspace.id.clone(), PermissionType::Write)
+                .await?,
+            CreatePermissionOutcome::AlreadyMember
+        ));
+        assert!(matches!(
+            storage
+                .create_permission("member@xxx.xx", "not_exists".into(), PermissionType::Read)
+                .await?,
+            CreatePermissionOutcome::WorkspaceNotInvitable
+        ));
+
+        // rejected before anything is inserted
+        assert!(matches!(
+            storage
+                .create_permission("not-an-email", workspace.id.clone(), PermissionType::Read)
+                .await,
+            Err(StorageError::InvalidEmail(email)) if email == "not-an-email"
+        ));
+        assert_eq!(
+            storage
+                .get_workspace_by_id(workspace.id.clone())
+                .await?
+                .unwrap()
+                .member_count,
+            2
+        );
+
+        // one owner per workspace
+        assert!(matches!(
+            storage
+                .create_permission("other@xxx.xx", workspace.id.clone(), PermissionType::Owner)
+                .await,
+            Err(StorageError::Conflict)
+        ));
+
+        let updated = storage
+            .update_workspace(workspace.id.clone(), UpdateWorkspace { public: true })
+            .await?
+            .unwrap();
+        assert!(updated.public);
+        assert!(
+            storage
+                .can_read_workspace("not_exists".into(), workspace.id.clone())
+                .await?
+        );
+
+        assert!(storage.delete_permission(permission_id).await?);
+        assert!(storage
+            .get_permission(member.id.clone(), workspace.id.clone())
+            .await?
+            .is_none());
+        assert!(storage.delete_workspace(workspace.id.clone()).await?);
+        assert!(storage
+            .get_workspace_by_id(workspace.id.clone())
+            .await?
+            .is_none());
+        assert!(storage
+            .get_user_workspaces(owner.id.clone())
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn storage_cloud_database() -> anyhow::Result<()> {
+        let pool = CloudDatabase::init_pool("sqlite::memory:").await?;
+        storage_behaviour(&pool).await
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn storage_memory() -> anyhow::Result<()> {
+        storage_behaviour(&crate::MemoryStorage::default()).await
+    }
+}
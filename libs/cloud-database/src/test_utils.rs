@@ -0,0 +1,228 @@
+use super::{
+    model::{CreateUser, PermissionType, Workspace},
+    storage::DbStorage,
+    types::{StorageError, StorageResult},
+    CloudDatabase, UsersModel,
+};
+use sea_orm::DbErr;
+use std::ops::Deref;
+
+/// An isolated database for a single test, see [`CloudDatabase::new_test`].
+pub struct TestDatabase {
+    db: CloudDatabase,
+    /// dropped after `db`, removing the schema it used
+    #[cfg(feature = "postgres")]
+    _schema: Option<postgres::Schema>,
+}
+
+impl Deref for TestDatabase {
+    type Target = CloudDatabase;
+
+    fn deref(&self) -> &Self::Target {
+        &self.db
+    }
+}
+
+impl CloudDatabase {
+    /// A migrated database that no other test shares.
+    ///
+    /// With `POSTGRES_DATABASE_URL` set (and the `postgres` feature) the
+    /// tables live in a uniquely named schema, dropped with the returned
+    /// [`TestDatabase`]. Otherwise a fresh in-memory SQLite database is used.
+    pub async fn new_test() -> Result<TestDatabase, DbErr> {
+        #[cfg(feature = "postgres")]
+        if let Ok(url) = std::env::var("POSTGRES_DATABASE_URL") {
+            let schema = postgres::Schema::create(url).await?;
+            return Ok(TestDatabase {
+                db: CloudDatabase::init_pool(&schema.url()).await?,
+                _schema: Some(schema),
+            });
+        }
+
+        Ok(TestDatabase {
+            db: CloudDatabase::init_pool("sqlite::memory:").await?,
+            #[cfg(feature = "postgres")]
+            _schema: None,
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use jwst_logger::warn;
+    use nanoid::nanoid;
+    use sea_orm::{ConnectionTrait, Database, DbErr};
+
+    const ALPHABET: [char; 36] = [
+        'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
+        's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+    ];
+
+    pub(super) struct Schema {
+        url: String,
+        name: String,
+    }
+
+    impl Schema {
+        pub(super) async fn create(url: String) -> Result<Self, DbErr> {
+            let name = format!("test_{}", nanoid!(12, &ALPHABET));
+            let conn = Database::connect(&url).await?;
+            conn.execute_unprepared(&format!("CREATE SCHEMA {name}"))
+                .await?;
+            conn.close().await?;
+            Ok(Self { url, name })
+        }
+
+        /// `url` with the schema as the only entry of the search path, so
+        /// migrations and queries never touch `public`.
+        pub(super) fn url(&self) -> String {
+            let separator = if self.url.contains('?') { '&' } else { '?' };
+            format!(
+                "{}{separator}options=-c%20search_path%3D{}",
+                self.url, self.name
+            )
+        }
+    }
+
+    impl Drop for Schema {
+        fn drop(&mut self) {
+            let url = self.url.clone();
+            let sql = format!("DROP SCHEMA IF EXISTS {} CASCADE", self.name);
+            // drop may run inside the test's runtime, which can't be blocked on
+            let dropped = std::thread::spawn(move || {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?
+                    .block_on(async {
+                        let conn = Database::connect(&url).await?;
+                        conn.execute_unprepared(&sql).await?;
+                        conn.close().await
+                    })
+                    .map_err(std::io::Error::other)
+            })
+            .join();
+            match dropped {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => warn!("failed to drop test schema {}: {e}", self.name),
+                Err(_) => warn!(
+                    "failed to drop test schema {}: dropping panicked",
+                    self.name
+                ),
+            }
+        }
+    }
+}
+
+/// Setup shared by tests of the crates built on top of the database.
+pub mod fixtures {
+    use super::*;
+
+    /// Password of the users created from [`user`].
+    pub const PASSWORD: &str = "password";
+
+    /// Signup data for `email`, named after its local part.
+    pub fn user(email: &str) -> CreateUser {
+        CreateUser {
+            name: email.split('@').next().unwrap_or(email).to_string(),
+            avatar_url: None,
+            email: email.to_string(),
+            password: PASSWORD.to_string(),
+        }
+    }
+
+    pub struct WorkspaceWithMembers {
+        pub workspace: Workspace,
+        pub owner: UsersModel,
+        /// in the order they were given, each with an accepted permission
+        pub members: Vec<UsersModel>,
+    }
+
+    /// A normal workspace owned by a new user `owner`, with a new user for
+    /// every entry of `members` that already accepted its invitation.
+    pub async fn workspace_with_members(
+        storage: &dyn DbStorage,
+        owner: &str,
+        members: &[(&str, PermissionType)],
+    ) -> StorageResult<WorkspaceWithMembers> {
+        let owner = storage
+            .create_user(user(owner))
+            .await?
+            .ok_or(StorageError::Conflict)?;
+        let workspace = storage.create_normal_workspace(owner.id.clone()).await?;
+
+        let mut users = Vec::with_capacity(members.len());
+        for (email, permission_type) in members {
+            let member = storage
+                .create_user(user(email))
+                .await?
+                .ok_or(StorageError::Conflict)?;
+            let (permission_id, _) = storage
+                .create_permission(email, workspace.id.clone(), permission_type.clone())
+                .await?
+                .created()
+                .ok_or(StorageError::Conflict)?;
+            storage.accept_permission(permission_id).await?;
+            users.push(member);
+        }
+
+        Ok(WorkspaceWithMembers {
+            workspace,
+            owner,
+            members: users,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fixtures::*, *};
+    use crate::model::UserLogin;
+
+    #[tokio::test]
+    async fn test_utils_isolated() -> anyhow::Result<()> {
+        let first = CloudDatabase::new_test().await?;
+        let second = CloudDatabase::new_test().await?;
+        // start test
+        first.create_user(user("xxx@xxx.xx")).await?.unwrap();
+        second.create_user(user("xxx@xxx.xx")).await?.unwrap();
+        assert!(first
+            .user_login(UserLogin {
+                email: "xxx@xxx.xx".into(),
+                password: PASSWORD.into(),
+            })
+            .await?
+            .is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_utils_workspace_with_members() -> anyhow::Result<()> {
+        let db = CloudDatabase::new_test().await?;
+        // start test
+        let fixture = workspace_with_members(
+            &*db,
+            "owner@xxx.xx",
+            &[
+                ("read@xxx.xx", PermissionType::Read),
+                ("write@xxx.xx", PermissionType::Write),
+            ],
+        )
+        .await?;
+        assert_eq!(fixture.members.len(), 2);
+
+        let detail = db
+            .get_workspace_by_id(fixture.workspace.id.clone())
+            .await?
+            .unwrap();
+        assert_eq!(detail.owner.unwrap().id, fixture.owner.id);
+        assert_eq!(detail.member_count, 3);
+        assert_eq!(
+            db.get_permission(fixture.members[1].id.clone(), fixture.workspace.id)
+                .await?,
+            Some(PermissionType::Write)
+        );
+
+        Ok(())
+    }
+}
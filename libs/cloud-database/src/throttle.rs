@@ -0,0 +1,44 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// How many failed password logins an email gets before
+/// [`CloudDatabase::user_login`](crate::CloudDatabase::user_login) locks it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoginThrottle {
+    /// failures within `window` that lock the email, 0 disables the lock
+    pub max_attempts: u32,
+    /// counted from the first failure, the lock lifts when it ends
+    pub window: Duration,
+}
+
+impl Default for LoginThrottle {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            window: Duration::minutes(15),
+        }
+    }
+}
+
+impl LoginThrottle {
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Time left on the lock of an email with `count` failures in the
+    /// window started at `window_start`, None when it isn't locked.
+    pub(crate) fn locked_for(
+        &self,
+        count: i32,
+        window_start: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Option<Duration> {
+        if self.max_attempts == 0 || count < self.max_attempts as i32 {
+            return None;
+        }
+        let remaining = window_start + self.window - now;
+        (remaining > Duration::zero()).then_some(remaining)
+    }
+}
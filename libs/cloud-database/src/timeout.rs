@@ -0,0 +1,72 @@
+use super::types::{StorageError, StorageResult};
+use sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr};
+use std::{future::Future, time::Duration};
+
+/// Per-call overrides, see [`CloudDatabase::with_query_options`](super::CloudDatabase::with_query_options).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueryOptions {
+    /// how long a single query may run, `None` keeps the database default
+    pub timeout: Option<Duration>,
+}
+
+/// Fail with [`StorageError::Timeout`] when `query` doesn't complete in time.
+///
+/// Dropping the future stops waiting for the query, the statement itself may
+/// still run to completion on its connection.
+pub(super) async fn with_timeout<T, Fut>(timeout: Option<Duration>, query: Fut) -> StorageResult<T>
+where
+    Fut: Future<Output = Result<T, DbErr>>,
+{
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, query)
+            .await
+            .map_err(|_| StorageError::Timeout)?
+            .map_err(StorageError::from),
+        None => query.await.map_err(StorageError::from),
+    }
+}
+
+/// Connect to the database, on Postgres every connection of the pool also
+/// gets a `statement_timeout` so the server cancels slow statements itself.
+pub(super) async fn connect(
+    options: ConnectOptions,
+    timeout: Option<Duration>,
+) -> Result<DatabaseConnection, DbErr> {
+    match timeout {
+        #[cfg(feature = "postgres")]
+        Some(timeout) if sea_orm::DbBackend::Postgres.is_prefix_of(options.get_url()) => {
+            connect_postgres(options, timeout).await
+        }
+        _ => Database::connect(options).await,
+    }
+}
+
+#[cfg(feature = "postgres")]
+async fn connect_postgres(
+    options: ConnectOptions,
+    timeout: Duration,
+) -> Result<DatabaseConnection, DbErr> {
+    use sea_orm::{RuntimeErr, SqlxPostgresConnector};
+    use sqlx::postgres::PgConnectOptions;
+
+    let connect_options = options
+        .get_url()
+        .parse::<PgConnectOptions>()
+        .map_err(|e| DbErr::Conn(RuntimeErr::SqlxError(e)))?;
+    let sql = format!("SET statement_timeout = {}", timeout.as_millis());
+    let pool = options
+        .pool_options::<sqlx::Postgres>()
+        .after_connect(move |conn, _| {
+            let sql = sql.clone();
+            Box::pin(async move {
+                sqlx::Executor::execute(conn, sql.as_str())
+                    .await
+                    .map(|_| ())
+            })
+        })
+        .connect_with(connect_options)
+        .await
+        .map_err(|e| DbErr::Conn(RuntimeErr::SqlxError(e)))?;
+
+    Ok(SqlxPostgresConnector::from_sqlx_postgres_pool(pool))
+}
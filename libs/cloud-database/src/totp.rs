@@ -0,0 +1,299 @@
+//! Time-based one-time passwords (RFC 6238) for two-factor logins.
+//!
+//! Codes are 6 digits over 30 second steps of HMAC-SHA1, the parameters every
+//! authenticator app uses by default. A code is accepted one step early or
+//! late to absorb clock drift. Secrets are stored sealed with a server key
+//! (ChaCha20-Poly1305, bound to the user id), so a copy of the database alone
+//! can't generate codes.
+use super::crypto::hex;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use sha1::Sha1;
+use std::fmt;
+
+/// Seconds each code is valid for.
+const TOTP_STEP: i64 = 30;
+const DIGITS: u32 = 6;
+/// steps accepted either side of the current one
+const SKEW: i64 = 1;
+/// 160 bits, the size of an HMAC-SHA1 key recommended by RFC 4226
+const SECRET_LEN: usize = 20;
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+/// 80 bits, 16 base32 characters
+const RECOVERY_CODE_LEN: usize = 10;
+
+/// Key sealing the stored TOTP secrets, see
+/// [`CloudDatabase::with_totp_key`](crate::CloudDatabase::with_totp_key).
+///
+/// Losing or changing it makes every enrolled secret unreadable, the users
+/// then have to enroll again.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TotpKey([u8; 32]);
+
+impl TotpKey {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self(key)
+    }
+
+    fn key(&self) -> LessSafeKey {
+        // only fails for a key of the wrong length
+        LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, &self.0).unwrap())
+    }
+
+    /// `secret` encrypted for `user_id`, hex encoded with its nonce.
+    pub(crate) fn seal(&self, user_id: &str, secret: &[u8]) -> String {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let mut sealed = secret.to_vec();
+        // only fails for inputs larger than ring accepts
+        self.key()
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(user_id.as_bytes()),
+                &mut sealed,
+            )
+            .unwrap();
+        hex(&[&nonce[..], &sealed].concat())
+    }
+
+    /// The secret sealed for `user_id`, None when it was sealed with another
+    /// key, for another user or is corrupted.
+    pub(crate) fn open(&self, user_id: &str, sealed: &str) -> Option<Vec<u8>> {
+        let bytes = from_hex(sealed)?;
+        if bytes.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+        let mut sealed = sealed.to_vec();
+        let secret = self
+            .key()
+            .open_in_place(
+                Nonce::try_assume_unique_for_key(nonce).ok()?,
+                Aad::from(user_id.as_bytes()),
+                &mut sealed,
+            )
+            .ok()?;
+        Some(secret.to_vec())
+    }
+}
+
+impl fmt::Debug for TotpKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TotpKey(..)")
+    }
+}
+
+pub(crate) fn new_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; SECRET_LEN];
+    OsRng.fill_bytes(&mut secret);
+    secret
+}
+
+/// A recovery code to show the user once, as four dash separated groups of
+/// lowercase base32.
+pub(crate) fn new_recovery_code() -> String {
+    let mut bytes = [0u8; RECOVERY_CODE_LEN];
+    OsRng.fill_bytes(&mut bytes);
+    let encoded = base32(&bytes).to_lowercase();
+    encoded
+        .as_bytes()
+        .chunks(4)
+        .map(|group| std::str::from_utf8(group).unwrap())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// A recovery code as typed by the user in the form it's hashed in, ignoring
+/// case, dashes and whitespace.
+pub(crate) fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The step `at` falls in.
+pub(crate) fn step(at: DateTime<Utc>) -> i64 {
+    at.timestamp().div_euclid(TOTP_STEP)
+}
+
+/// The code of `secret` for `step` (RFC 4226 with the step as counter).
+pub(crate) fn code(secret: &[u8], step: i64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC takes keys of any size");
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = (hash[hash.len() - 1] & 0xf) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    )
+}
+
+/// The step `code` was generated for, when it's valid around `at` and
+/// newer than `last_step`, the step of the last code accepted.
+pub(crate) fn verify(
+    secret: &[u8],
+    code: &str,
+    at: DateTime<Utc>,
+    last_step: Option<i64>,
+) -> Option<i64> {
+    if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let current = step(at);
+    // every candidate is compared, in time independent of which one matches
+    (current - SKEW..=current + SKEW)
+        .filter(|step| !matches!(last_step, Some(last) if *step <= last))
+        .fold(None, |matched, step| {
+            let expected = self::code(secret, step);
+            let equal = expected
+                .bytes()
+                .zip(code.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0;
+            matched.or(equal.then_some(step))
+        })
+}
+
+/// `bytes` in unpadded base32, the form authenticator apps take secrets in.
+pub(crate) fn base32(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 8).div_ceil(5));
+    for chunk in bytes.chunks(5) {
+        let mut buf = [0u8; 5];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let bits = u64::from_be_bytes([0, 0, 0, buf[0], buf[1], buf[2], buf[3], buf[4]]);
+        for i in 0..(chunk.len() * 8).div_ceil(5) {
+            encoded.push(BASE32[((bits >> (35 - i * 5)) & 0x1f) as usize] as char);
+        }
+    }
+    encoded
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [_, _] => u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Inverse of [`base32`], for tests acting as the authenticator app.
+    pub(crate) fn from_base32(encoded: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let (mut bits, mut len) = (0u64, 0);
+        for c in encoded.bytes() {
+            let value = BASE32.iter().position(|b| *b == c).unwrap() as u64;
+            bits = (bits << 5) | value;
+            len += 5;
+            if len >= 8 {
+                len -= 8;
+                bytes.push((bits >> len) as u8);
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn totp_rfc6238_vectors() {
+        let secret = b"12345678901234567890";
+        for (time, expected) in [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ] {
+            let at = Utc.timestamp_opt(time, 0).unwrap();
+            assert_eq!(code(secret, step(at)), expected, "{time}");
+        }
+    }
+
+    #[test]
+    fn totp_verify_window() {
+        let secret = b"12345678901234567890";
+        let at = Utc.timestamp_opt(1111111109, 0).unwrap();
+        let current = step(at);
+
+        assert_eq!(verify(secret, "081804", at, None), Some(current));
+        // a step early or late
+        let early = code(secret, current - 1);
+        let late = code(secret, current + 1);
+        assert_eq!(verify(secret, &early, at, None), Some(current - 1));
+        assert_eq!(verify(secret, &late, at, None), Some(current + 1));
+        // but no further
+        assert_eq!(verify(secret, &code(secret, current - 2), at, None), None);
+        assert_eq!(verify(secret, &code(secret, current + 2), at, None), None);
+        // a code is only accepted once
+        assert_eq!(verify(secret, "081804", at, Some(current)), None);
+        assert_eq!(verify(secret, &late, at, Some(current)), Some(current + 1));
+
+        assert_eq!(verify(secret, "81804", at, None), None);
+        assert_eq!(verify(secret, "08180a", at, None), None);
+        assert_eq!(verify(b"another secret", "081804", at, None), None);
+    }
+
+    #[test]
+    fn totp_base32() {
+        // RFC 4648 test vectors, without padding
+        for (plain, encoded) in [
+            ("", ""),
+            ("f", "MY"),
+            ("fo", "MZXQ"),
+            ("foo", "MZXW6"),
+            ("foob", "MZXW6YQ"),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI"),
+        ] {
+            assert_eq!(base32(plain.as_bytes()), encoded);
+            assert_eq!(from_base32(encoded), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn totp_key_seal() {
+        let key = TotpKey::new([1; 32]);
+        let secret = new_secret();
+        let sealed = key.seal("user", &secret);
+        assert_eq!(key.open("user", &sealed), Some(secret.clone()));
+        // random nonce
+        assert_ne!(key.seal("user", &secret), sealed);
+
+        assert_eq!(key.open("other", &sealed), None);
+        assert_eq!(TotpKey::new([2; 32]).open("user", &sealed), None);
+        assert_eq!(key.open("user", &sealed[..sealed.len() - 2]), None);
+        assert_eq!(key.open("user", "not hex"), None);
+        assert!(!format!("{key:?}").contains('1'));
+    }
+
+    #[test]
+    fn totp_recovery_code() {
+        let code = new_recovery_code();
+        assert_eq!(code.len(), 19);
+        assert_eq!(code.matches('-').count(), 3);
+        assert_ne!(code, new_recovery_code());
+
+        let normalized = normalize_recovery_code(&code);
+        assert_eq!(normalized.len(), 16);
+        assert_eq!(normalize_recovery_code(&code.to_uppercase()), normalized);
+        assert_eq!(
+            normalize_recovery_code(&format!(" {} ", code.replace('-', " "))),
+            normalized
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{crypto::PasswordHashError, schema::SchemaReport};
+use sea_orm::{DbErr, RuntimeErr};
+use sqlx::error::DatabaseError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("record not found")]
+    NotFound,
+    #[error("record already exists")]
+    Conflict,
+    #[error("permission denied")]
+    PermissionDenied,
+    #[error("db error: {0}")]
+    Database(#[source] DbErr),
+    #[error("password hash error: {0}")]
+    PasswordHash(#[from] PasswordHashError),
+    #[error("password must be at least {0} characters")]
+    PasswordTooShort(usize),
+    /// removing the user's only way to log in
+    #[error("a password must be set first")]
+    PasswordRequired,
+    /// the user still owns these workspaces shared with other members
+    #[error("ownership of workspaces {0:?} must be transferred first")]
+    OwnershipTransferRequired(Vec<String>),
+    /// too many failed logins for the email, see [`crate::LoginThrottle`]
+    #[error("login locked, retry in {}s", retry_after.num_seconds())]
+    Locked { retry_after: chrono::Duration },
+    /// the password was right, the login needs a TOTP code as well, see
+    /// [`crate::CloudDatabase::user_login_with_totp`]
+    #[error("two-factor code required")]
+    TotpRequired,
+    /// the account was suspended, see [`crate::CloudDatabase::suspend_user`]
+    #[error("account suspended")]
+    Suspended,
+    /// no [`crate::TotpKey`] is configured to seal TOTP secrets with
+    #[error("two-factor authentication is not configured")]
+    TotpUnavailable,
+    /// rejected by [`crate::validate_email`]
+    #[error("invalid email: {0}")]
+    InvalidEmail(String),
+    #[error("invalid timezone: {0}")]
+    InvalidTimezone(String),
+    #[error("schema drift: {0}")]
+    SchemaDrift(SchemaReport),
+    #[error("connection pool exhausted")]
+    PoolExhausted,
+    #[error("connection pool closed")]
+    PoolClosed,
+    #[error("database unavailable: {0}")]
+    Unavailable(#[source] sqlx::Error),
+    #[error("query timed out")]
+    Timeout,
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+impl From<DbErr> for StorageError {
+    fn from(err: DbErr) -> Self {
+        match &err {
+            DbErr::RecordNotFound(_) => StorageError::NotFound,
+            DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+            | DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+                if is_unique_violation(e.as_ref()) =>
+            {
+                StorageError::Conflict
+            }
+            DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+            | DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+                if e.code().as_deref() == Some("57014") =>
+            {
+                // postgres: query_canceled, raised when statement_timeout expires
+                StorageError::Timeout
+            }
+            _ => StorageError::Database(err),
+        }
+    }
+}
+
+fn is_unique_violation(err: &dyn DatabaseError) -> bool {
+    match err.code().as_deref() {
+        // postgres: unique_violation
+        Some("23505") => true,
+        // sqlite: SQLITE_CONSTRAINT_PRIMARYKEY, SQLITE_CONSTRAINT_UNIQUE
+        Some("1555") | Some("2067") => true,
+        #[cfg(feature = "mysql")]
+        _ => err
+            .try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>()
+            // mysql: ER_DUP_ENTRY
+            .map(|e| e.number() == 1062)
+            .unwrap_or(false),
+        #[cfg(not(feature = "mysql"))]
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{borrow::Cow, error::Error, fmt};
+
+    #[derive(Debug)]
+    struct CodedError(&'static str);
+
+    impl fmt::Display for CodedError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "coded error {}", self.0)
+        }
+    }
+
+    impl Error for CodedError {}
+
+    impl DatabaseError for CodedError {
+        fn message(&self) -> &str {
+            self.0
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.0))
+        }
+
+        fn as_error(&self) -> &(dyn Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn Error + Send + Sync + 'static> {
+            self
+        }
+    }
+
+    fn db_err(code: &'static str) -> DbErr {
+        DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(Box::new(
+            CodedError(code),
+        ))))
+    }
+
+    #[test]
+    fn storage_error_from_db_err() {
+        let convert = StorageError::from;
+        assert!(matches!(convert(db_err("23505")), StorageError::Conflict));
+        assert!(matches!(convert(db_err("2067")), StorageError::Conflict));
+        assert!(matches!(convert(db_err("1555")), StorageError::Conflict));
+        // foreign key violations are not conflicts
+        assert!(matches!(
+            convert(db_err("23503")),
+            StorageError::Database(_)
+        ));
+        assert!(matches!(convert(db_err("57014")), StorageError::Timeout));
+        assert!(matches!(
+            convert(DbErr::RecordNotFound("xxx".into())),
+            StorageError::NotFound
+        ));
+        assert!(matches!(
+            convert(DbErr::ConnectionAcquire),
+            StorageError::Database(_)
+        ));
+    }
+}